@@ -0,0 +1,5 @@
+ALTER TABLE games
+DROP COLUMN max_guesses;
+
+ALTER TABLE game_templates
+DROP COLUMN max_guesses;
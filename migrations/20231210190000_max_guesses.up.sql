@@ -0,0 +1,5 @@
+ALTER TABLE game_templates
+ADD COLUMN max_guesses INT NOT NULL DEFAULT 1;
+
+ALTER TABLE games
+ADD COLUMN max_guesses INT NOT NULL DEFAULT 1;
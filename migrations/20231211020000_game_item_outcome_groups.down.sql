@@ -0,0 +1,4 @@
+DROP INDEX idx_game_item_outcomes_group_id;
+
+ALTER TABLE game_item_outcomes
+DROP COLUMN group_id;
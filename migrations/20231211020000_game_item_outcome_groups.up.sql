@@ -0,0 +1,9 @@
+ALTER TABLE game_item_outcomes
+ADD COLUMN group_id BIGINT;
+
+UPDATE game_item_outcomes SET group_id = outcome_id;
+
+ALTER TABLE game_item_outcomes
+ALTER COLUMN group_id SET NOT NULL;
+
+CREATE INDEX idx_game_item_outcomes_group_id ON game_item_outcomes(group_id);
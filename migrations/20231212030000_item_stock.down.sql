@@ -0,0 +1,5 @@
+ALTER TABLE game_items
+DROP COLUMN stock;
+
+ALTER TABLE game_item_templates
+DROP COLUMN stock;
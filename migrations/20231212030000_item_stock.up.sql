@@ -0,0 +1,6 @@
+-- NULL stock means the item can drop any number of times
+ALTER TABLE game_item_templates
+ADD COLUMN stock INT DEFAULT 1;
+
+ALTER TABLE game_items
+ADD COLUMN stock INT DEFAULT 1;
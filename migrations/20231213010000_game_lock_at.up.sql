@@ -0,0 +1,3 @@
+-- Unix time in milliseconds at which an unlocked game locks itself
+ALTER TABLE games
+ADD COLUMN lock_at BIGINT;
@@ -0,0 +1,11 @@
+ALTER TABLE player_guesses
+DROP COLUMN received_at;
+
+ALTER TABLE games
+DROP COLUMN locked_at;
+
+ALTER TABLE games
+DROP COLUMN stream_delay;
+
+ALTER TABLE game_templates
+DROP COLUMN stream_delay;
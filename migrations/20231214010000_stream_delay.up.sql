@@ -0,0 +1,12 @@
+-- Stream delay is in seconds, timestamps are Unix time in milliseconds
+ALTER TABLE game_templates
+ADD COLUMN stream_delay INT NOT NULL DEFAULT 0;
+
+ALTER TABLE games
+ADD COLUMN stream_delay INT NOT NULL DEFAULT 0;
+
+ALTER TABLE games
+ADD COLUMN locked_at BIGINT;
+
+ALTER TABLE player_guesses
+ADD COLUMN received_at BIGINT;
@@ -0,0 +1,16 @@
+ALTER TABLE player_guesses
+DROP COLUMN bonus_points,
+DROP COLUMN response_ms;
+
+ALTER TABLE games
+DROP COLUMN unlocked_at,
+DROP COLUMN speed_bonus_decay_secs,
+DROP COLUMN speed_bonus_first_n,
+DROP COLUMN speed_bonus_points,
+DROP COLUMN speed_bonus;
+
+ALTER TABLE game_templates
+DROP COLUMN speed_bonus_decay_secs,
+DROP COLUMN speed_bonus_first_n,
+DROP COLUMN speed_bonus_points,
+DROP COLUMN speed_bonus;
@@ -0,0 +1,17 @@
+ALTER TABLE game_templates
+ADD COLUMN speed_bonus TEXT NOT NULL DEFAULT 'NONE',
+ADD COLUMN speed_bonus_points INT NOT NULL DEFAULT 0,
+ADD COLUMN speed_bonus_first_n INT NOT NULL DEFAULT 0,
+ADD COLUMN speed_bonus_decay_secs INT NOT NULL DEFAULT 0;
+
+ALTER TABLE games
+ADD COLUMN speed_bonus TEXT NOT NULL DEFAULT 'NONE',
+ADD COLUMN speed_bonus_points INT NOT NULL DEFAULT 0,
+ADD COLUMN speed_bonus_first_n INT NOT NULL DEFAULT 0,
+ADD COLUMN speed_bonus_decay_secs INT NOT NULL DEFAULT 0,
+ADD COLUMN unlocked_at BIGINT;
+
+-- Milliseconds between the round opening and the guess, set once the drop is revealed
+ALTER TABLE player_guesses
+ADD COLUMN response_ms BIGINT,
+ADD COLUMN bonus_points INT NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+ALTER TABLE game_players
+DROP COLUMN sudden_death,
+DROP COLUMN guess_changes;
+
+ALTER TABLE games
+DROP COLUMN sudden_death,
+DROP COLUMN tie_break;
+
+ALTER TABLE game_templates
+DROP COLUMN tie_break;
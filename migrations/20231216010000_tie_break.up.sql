@@ -0,0 +1,11 @@
+ALTER TABLE game_templates
+ADD COLUMN tie_break VARCHAR(128) NOT NULL DEFAULT 'NONE';
+
+ALTER TABLE games
+ADD COLUMN tie_break VARCHAR(128) NOT NULL DEFAULT 'NONE',
+ADD COLUMN sudden_death BOOLEAN NOT NULL DEFAULT false;
+
+-- sudden_death marks the tied players who may keep guessing
+ALTER TABLE game_players
+ADD COLUMN guess_changes INT NOT NULL DEFAULT 0,
+ADD COLUMN sudden_death BOOLEAN NOT NULL DEFAULT false;
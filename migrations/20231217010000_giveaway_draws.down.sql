@@ -0,0 +1,3 @@
+DROP INDEX idx_giveaway_draws_game_code ON giveaway_draws;
+
+DROP TABLE IF EXISTS giveaway_draws;
@@ -0,0 +1,13 @@
+-- entrants is the sorted pool the seed was applied to, so anyone can re-run the draw
+CREATE TABLE IF NOT EXISTS giveaway_draws (
+    giveaway_draw_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    pool VARCHAR(128) NOT NULL,
+    seed BIGINT NOT NULL,
+    draw_count INT NOT NULL,
+    entrants TEXT[] NOT NULL,
+    winners TEXT[] NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_giveaway_draws_game_code ON giveaway_draws(game_code);
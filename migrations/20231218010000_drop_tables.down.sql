@@ -0,0 +1,12 @@
+ALTER TABLE game_item_outcomes
+DROP COLUMN roll_seed;
+
+ALTER TABLE games
+DROP COLUMN roll_seed_hash,
+DROP COLUMN roll_seed;
+
+ALTER TABLE game_items
+DROP COLUMN drop_weight;
+
+ALTER TABLE game_item_templates
+DROP COLUMN drop_weight;
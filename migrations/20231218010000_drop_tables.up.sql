@@ -0,0 +1,13 @@
+ALTER TABLE game_item_templates
+ADD COLUMN drop_weight INT;
+
+ALTER TABLE game_items
+ADD COLUMN drop_weight INT;
+
+-- roll_seed stays secret while locked, only its hash is shown until the drop is rolled
+ALTER TABLE games
+ADD COLUMN roll_seed BIGINT,
+ADD COLUMN roll_seed_hash VARCHAR(128);
+
+ALTER TABLE game_item_outcomes
+ADD COLUMN roll_seed BIGINT;
@@ -0,0 +1,8 @@
+DROP INDEX idx_player_guesses_round_id ON player_guesses;
+
+ALTER TABLE player_guesses
+DROP COLUMN round_id;
+
+DROP INDEX idx_game_rounds_game_code ON game_rounds;
+
+DROP TABLE IF EXISTS game_rounds;
@@ -0,0 +1,41 @@
+-- outcome_id is the group_id of the outcomes revealed to resolve the round
+CREATE TABLE IF NOT EXISTS game_rounds (
+    game_round_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    status VARCHAR(128) NOT NULL,
+
+    opened_at BIGINT NOT NULL,
+    locked_at BIGINT,
+    resolved_at BIGINT,
+
+    outcome_id BIGINT,
+    closed_by VARCHAR(128),
+
+    guesses_count INT NOT NULL DEFAULT 0,
+    players_count INT NOT NULL DEFAULT 0,
+    correct_count INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_game_rounds_game_code ON game_rounds(game_code);
+
+ALTER TABLE player_guesses
+ADD COLUMN round_id BIGINT;
+
+CREATE INDEX idx_player_guesses_round_id ON player_guesses(round_id);
+
+-- Games already running pick up their current round
+INSERT INTO game_rounds (game_code, status, opened_at, locked_at)
+SELECT
+    game_code,
+    CASE WHEN is_locked THEN 'LOCKED' ELSE 'OPEN' END,
+    COALESCE(unlocked_at, active_at * 1000),
+    locked_at
+FROM games
+WHERE status = 'ACTIVE';
+
+UPDATE player_guesses
+SET round_id = game_rounds.game_round_id
+FROM game_rounds
+WHERE
+    game_rounds.game_code = player_guesses.game_code AND
+    player_guesses.outcome_id IS NULL;
@@ -0,0 +1,3 @@
+-- Games in the LOBBY status start by themselves once start_at passes
+ALTER TABLE games
+ADD COLUMN start_at BIGINT;
@@ -0,0 +1,3 @@
+-- Set while a game is PAUSED so resuming can push the lock timer back by the time spent paused
+ALTER TABLE games
+ADD COLUMN paused_at BIGINT;
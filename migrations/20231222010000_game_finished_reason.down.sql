@@ -0,0 +1,5 @@
+ALTER TABLE games
+DROP COLUMN finished_notice_seen;
+
+ALTER TABLE games
+DROP COLUMN finished_reason;
@@ -0,0 +1,7 @@
+-- Why the game was finished, NULL for games finished before this was tracked
+ALTER TABLE games
+ADD COLUMN finished_reason VARCHAR(128);
+
+-- Hosts get told once on their games list when the sweeper finished one of their games
+ALTER TABLE games
+ADD COLUMN finished_notice_seen BOOLEAN NOT NULL DEFAULT true;
@@ -0,0 +1,16 @@
+ALTER TABLE games
+DROP COLUMN finished_by;
+
+ALTER TABLE game_rounds
+DROP COLUMN resolved_by;
+
+ALTER TABLE game_rounds
+DROP COLUMN locked_by;
+
+DROP INDEX idx_user_cohosts_host_user_id_twitch_login ON user_cohosts;
+
+DROP TABLE IF EXISTS user_cohosts;
+
+DROP INDEX idx_game_cohosts_game_code_twitch_login ON game_cohosts;
+
+DROP TABLE IF EXISTS game_cohosts;
@@ -0,0 +1,29 @@
+-- Co-hosts are granted by Twitch login so they can be added before they've ever signed in
+CREATE TABLE IF NOT EXISTS game_cohosts (
+    game_cohost_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    twitch_login VARCHAR(128) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_game_cohosts_game_code_twitch_login ON game_cohosts(game_code, twitch_login);
+
+-- Standing co-hosts run every game of the host's
+CREATE TABLE IF NOT EXISTS user_cohosts (
+    user_cohost_id SERIAL PRIMARY KEY,
+    host_user_id VARCHAR(128) NOT NULL,
+    twitch_login VARCHAR(128) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_user_cohosts_host_user_id_twitch_login ON user_cohosts(host_user_id, twitch_login);
+
+-- Who ran each host action, NULL when the server did it on its own
+ALTER TABLE game_rounds
+ADD COLUMN locked_by VARCHAR(128);
+
+ALTER TABLE game_rounds
+ADD COLUMN resolved_by VARCHAR(128);
+
+ALTER TABLE games
+ADD COLUMN finished_by VARCHAR(128);
@@ -0,0 +1,7 @@
+DROP INDEX idx_user_blocks_host_user_id_twitch_login ON user_blocks;
+
+DROP TABLE IF EXISTS user_blocks;
+
+DROP INDEX idx_game_bans_game_code_user_id ON game_bans;
+
+DROP TABLE IF EXISTS game_bans;
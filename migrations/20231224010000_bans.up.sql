@@ -0,0 +1,20 @@
+-- Players banned from a single game can't rejoin it
+CREATE TABLE IF NOT EXISTS game_bans (
+    game_ban_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    banned_by VARCHAR(128),
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_game_bans_game_code_user_id ON game_bans(game_code, user_id);
+
+-- Logins on a host's blocklist can't join any of their games
+CREATE TABLE IF NOT EXISTS user_blocks (
+    user_block_id SERIAL PRIMARY KEY,
+    host_user_id VARCHAR(128) NOT NULL,
+    twitch_login VARCHAR(128) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_user_blocks_host_user_id_twitch_login ON user_blocks(host_user_id, twitch_login);
@@ -0,0 +1,4 @@
+DROP INDEX idx_point_adjustments_player_id ON point_adjustments;
+DROP INDEX idx_point_adjustments_game_code ON point_adjustments;
+
+DROP TABLE IF EXISTS point_adjustments;
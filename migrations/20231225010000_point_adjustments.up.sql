@@ -0,0 +1,15 @@
+-- after_outcome_id is the latest drop group when the adjustment was made, NULL before the first drop,
+-- so the history can replay it in the right round
+CREATE TABLE IF NOT EXISTS point_adjustments (
+    point_adjustment_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    player_id BIGINT NOT NULL,
+    points INT NOT NULL,
+    reason TEXT NOT NULL,
+    adjusted_by VARCHAR(128),
+    after_outcome_id BIGINT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_point_adjustments_game_code ON point_adjustments(game_code);
+CREATE INDEX idx_point_adjustments_player_id ON point_adjustments(player_id);
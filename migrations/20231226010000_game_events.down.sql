@@ -0,0 +1,3 @@
+DROP INDEX idx_game_events_game_code ON game_events;
+
+DROP TABLE IF EXISTS game_events;
@@ -0,0 +1,12 @@
+-- Append-only log of every host action, written where the action is published.
+-- payload is the serialized HostActionType, so replays and analytics can read it back as-is
+CREATE TABLE IF NOT EXISTS game_events (
+    game_event_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    event_type VARCHAR(128) NOT NULL,
+    actor VARCHAR(128),
+    payload TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_game_events_game_code ON game_events(game_code);
@@ -0,0 +1 @@
+ALTER TABLE game_events DROP COLUMN source;
@@ -0,0 +1,2 @@
+-- Player actions are logged next to host actions so finished games can be replayed
+ALTER TABLE game_events ADD COLUMN source VARCHAR(128) NOT NULL DEFAULT 'HOST';
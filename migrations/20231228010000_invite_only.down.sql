@@ -0,0 +1,11 @@
+DROP INDEX idx_game_join_requests_game_code_user_id ON game_join_requests;
+
+DROP TABLE IF EXISTS game_join_requests;
+
+DROP INDEX idx_game_invites_game_code_twitch_login ON game_invites;
+
+DROP TABLE IF EXISTS game_invites;
+
+ALTER TABLE games DROP COLUMN require_approval;
+ALTER TABLE games DROP COLUMN invite_token;
+ALTER TABLE games DROP COLUMN invite_only;
@@ -0,0 +1,30 @@
+-- Invite-only games take players from the allowlist or holding the secret join link
+ALTER TABLE games
+ADD COLUMN invite_only BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE games
+ADD COLUMN invite_token VARCHAR(128);
+
+-- With approval on, every new player waits for the host to let them in
+ALTER TABLE games
+ADD COLUMN require_approval BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS game_invites (
+    game_invite_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    twitch_login VARCHAR(128) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_game_invites_game_code_twitch_login ON game_invites(game_code, twitch_login);
+
+CREATE TABLE IF NOT EXISTS game_join_requests (
+    game_join_request_id SERIAL PRIMARY KEY,
+    game_code VARCHAR(128) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    status VARCHAR(128) NOT NULL,
+    decided_by VARCHAR(128),
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_game_join_requests_game_code_user_id ON game_join_requests(game_code, user_id);
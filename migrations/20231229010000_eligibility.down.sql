@@ -0,0 +1,10 @@
+ALTER TABLE session_auths DROP COLUMN can_check_eligibility;
+ALTER TABLE csrf_tokens DROP COLUMN with_eligibility;
+
+ALTER TABLE games DROP COLUMN eligible_vips;
+ALTER TABLE games DROP COLUMN eligible_subscribers;
+ALTER TABLE games DROP COLUMN eligible_followers;
+
+ALTER TABLE game_templates DROP COLUMN eligible_vips;
+ALTER TABLE game_templates DROP COLUMN eligible_subscribers;
+ALTER TABLE game_templates DROP COLUMN eligible_followers;
@@ -0,0 +1,25 @@
+-- Players need to match one of the checked rules to join, nothing checked lets anyone in
+ALTER TABLE game_templates
+ADD COLUMN eligible_followers BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE game_templates
+ADD COLUMN eligible_subscribers BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE game_templates
+ADD COLUMN eligible_vips BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE games
+ADD COLUMN eligible_followers BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE games
+ADD COLUMN eligible_subscribers BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE games
+ADD COLUMN eligible_vips BOOLEAN NOT NULL DEFAULT false;
+
+-- Hosts connect once more with the follower, subscriber and VIP scopes so joins can be checked with their token
+ALTER TABLE csrf_tokens
+ADD COLUMN with_eligibility BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE session_auths
+ADD COLUMN can_check_eligibility BOOLEAN NOT NULL DEFAULT false;
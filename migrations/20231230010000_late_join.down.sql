@@ -0,0 +1,9 @@
+ALTER TABLE games DROP COLUMN late_join_catch_up;
+ALTER TABLE games DROP COLUMN late_join_drops;
+ALTER TABLE games DROP COLUMN late_join;
+ALTER TABLE games DROP COLUMN max_players;
+
+ALTER TABLE game_templates DROP COLUMN late_join_catch_up;
+ALTER TABLE game_templates DROP COLUMN late_join_drops;
+ALTER TABLE game_templates DROP COLUMN late_join;
+ALTER TABLE game_templates DROP COLUMN max_players;
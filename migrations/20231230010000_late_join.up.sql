@@ -0,0 +1,24 @@
+-- max_players NULL means no limit. late_join_drops only applies to the AFTER_DROPS policy
+ALTER TABLE game_templates
+ADD COLUMN max_players INT;
+
+ALTER TABLE game_templates
+ADD COLUMN late_join VARCHAR(128) NOT NULL DEFAULT 'ANYTIME';
+
+ALTER TABLE game_templates
+ADD COLUMN late_join_drops INT NOT NULL DEFAULT 0;
+
+ALTER TABLE game_templates
+ADD COLUMN late_join_catch_up BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE games
+ADD COLUMN max_players INT;
+
+ALTER TABLE games
+ADD COLUMN late_join VARCHAR(128) NOT NULL DEFAULT 'ANYTIME';
+
+ALTER TABLE games
+ADD COLUMN late_join_drops INT NOT NULL DEFAULT 0;
+
+ALTER TABLE games
+ADD COLUMN late_join_catch_up BOOLEAN NOT NULL DEFAULT false;
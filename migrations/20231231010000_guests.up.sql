@@ -0,0 +1,2 @@
+ALTER TABLE games
+ADD COLUMN allow_guests BOOLEAN NOT NULL DEFAULT false;
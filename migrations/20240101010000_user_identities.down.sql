@@ -0,0 +1,7 @@
+ALTER TABLE csrf_tokens DROP COLUMN provider;
+
+DROP INDEX idx_user_identities_user_id ON user_identities;
+DROP INDEX idx_user_identities_provider_subject ON user_identities;
+DROP TABLE IF EXISTS user_identities;
+
+ALTER TABLE users DROP COLUMN provider;
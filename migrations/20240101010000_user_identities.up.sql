@@ -0,0 +1,27 @@
+-- provider is what the account was first made with. Accounts made through Twitch keep the Twitch
+-- user id as their user_id, everything else gets a prefixed id of its own
+ALTER TABLE users
+ADD COLUMN provider VARCHAR(128) NOT NULL DEFAULT 'twitch';
+
+UPDATE users SET provider = 'guest' WHERE user_id LIKE 'guest:%';
+
+-- subject is the provider's own id for the person, the Twitch user id or the OIDC sub claim
+CREATE TABLE IF NOT EXISTS user_identities (
+    user_identity_id SERIAL PRIMARY KEY,
+    user_id VARCHAR(128) NOT NULL,
+    provider VARCHAR(128) NOT NULL,
+    subject VARCHAR(128) NOT NULL,
+    username VARCHAR(128) NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_user_identities_provider_subject ON user_identities(provider, subject);
+CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+
+INSERT INTO user_identities (user_id, provider, subject, username, created_at)
+SELECT user_id, 'twitch', user_id, username, EXTRACT(EPOCH FROM NOW())::BIGINT
+FROM users
+WHERE provider = 'twitch';
+
+ALTER TABLE csrf_tokens
+ADD COLUMN provider VARCHAR(128) NOT NULL DEFAULT 'twitch';
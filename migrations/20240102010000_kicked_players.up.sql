@@ -0,0 +1,2 @@
+ALTER TABLE game_players
+ADD COLUMN kicked_at BIGINT;
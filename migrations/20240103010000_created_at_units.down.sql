@@ -0,0 +1,2 @@
+ALTER TABLE game_events RENAME COLUMN created_at_ms TO created_at;
+UPDATE point_adjustments SET created_at = created_at * 1000;
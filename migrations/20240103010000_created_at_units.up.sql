@@ -0,0 +1,2 @@
+UPDATE point_adjustments SET created_at = created_at / 1000;
+ALTER TABLE game_events RENAME COLUMN created_at TO created_at_ms;
@@ -0,0 +1,4 @@
+ALTER TABLE giveaway_draws
+DROP COLUMN entrant_ids,
+DROP COLUMN entrant_guests,
+DROP COLUMN winner_ids;
@@ -0,0 +1,6 @@
+-- Draws are made over game_player_ids, names can repeat and guests pick their own.
+-- Draws from before these columns have them empty and still verify by name
+ALTER TABLE giveaway_draws
+ADD COLUMN entrant_ids BIGINT[] NOT NULL DEFAULT '{}',
+ADD COLUMN entrant_guests BOOLEAN[] NOT NULL DEFAULT '{}',
+ADD COLUMN winner_ids BIGINT[] NOT NULL DEFAULT '{}';
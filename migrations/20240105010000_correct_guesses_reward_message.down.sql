@@ -0,0 +1,7 @@
+UPDATE game_templates
+SET total_reward_message = '<USER> won with <POINTS>/<TOTAL> correct guesses'
+WHERE total_reward_message = '<USER> won with <CORRECT>/<TOTAL> correct guesses';
+
+UPDATE games
+SET total_reward_message = '<USER> won with <POINTS>/<TOTAL> correct guesses'
+WHERE total_reward_message = '<USER> won with <CORRECT>/<TOTAL> correct guesses';
@@ -0,0 +1,8 @@
+-- <POINTS> includes bonuses and adjustments, the old default meant correct guesses
+UPDATE game_templates
+SET total_reward_message = '<USER> won with <CORRECT>/<TOTAL> correct guesses'
+WHERE total_reward_message = '<USER> won with <POINTS>/<TOTAL> correct guesses';
+
+UPDATE games
+SET total_reward_message = '<USER> won with <CORRECT>/<TOTAL> correct guesses'
+WHERE total_reward_message = '<USER> won with <POINTS>/<TOTAL> correct guesses';
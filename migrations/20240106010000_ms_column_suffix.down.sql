@@ -0,0 +1,11 @@
+ALTER TABLE game_rounds RENAME COLUMN resolved_at_ms TO resolved_at;
+ALTER TABLE game_rounds RENAME COLUMN locked_at_ms TO locked_at;
+ALTER TABLE game_rounds RENAME COLUMN opened_at_ms TO opened_at;
+
+ALTER TABLE player_guesses RENAME COLUMN received_at_ms TO received_at;
+
+ALTER TABLE games RENAME COLUMN unlocked_at_ms TO unlocked_at;
+ALTER TABLE games RENAME COLUMN locked_at_ms TO locked_at;
+ALTER TABLE games RENAME COLUMN lock_at_ms TO lock_at;
+ALTER TABLE games RENAME COLUMN paused_at_ms TO paused_at;
+ALTER TABLE games RENAME COLUMN start_at_ms TO start_at;
@@ -0,0 +1,13 @@
+-- Millisecond columns get an _ms suffix so they can't be mistaken for the second-resolution
+-- created_at/active_at
+ALTER TABLE games RENAME COLUMN start_at TO start_at_ms;
+ALTER TABLE games RENAME COLUMN paused_at TO paused_at_ms;
+ALTER TABLE games RENAME COLUMN lock_at TO lock_at_ms;
+ALTER TABLE games RENAME COLUMN locked_at TO locked_at_ms;
+ALTER TABLE games RENAME COLUMN unlocked_at TO unlocked_at_ms;
+
+ALTER TABLE player_guesses RENAME COLUMN received_at TO received_at_ms;
+
+ALTER TABLE game_rounds RENAME COLUMN opened_at TO opened_at_ms;
+ALTER TABLE game_rounds RENAME COLUMN locked_at TO locked_at_ms;
+ALTER TABLE game_rounds RENAME COLUMN resolved_at TO resolved_at_ms;
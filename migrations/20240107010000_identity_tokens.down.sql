@@ -0,0 +1,6 @@
+ALTER TABLE user_identities
+DROP COLUMN access_token,
+DROP COLUMN refresh_token,
+DROP COLUMN expiry,
+DROP COLUMN can_chat,
+DROP COLUMN can_check_eligibility;
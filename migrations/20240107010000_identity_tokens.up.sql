@@ -0,0 +1,24 @@
+-- Tokens belong to the identity they were issued to, so signing in through another provider
+-- leaves the Twitch token that eligibility checks use alone
+ALTER TABLE user_identities
+ADD COLUMN access_token VARCHAR(1024) NOT NULL DEFAULT '',
+ADD COLUMN refresh_token VARCHAR(1024) NOT NULL DEFAULT '',
+ADD COLUMN expiry BIGINT NOT NULL DEFAULT 0,
+ADD COLUMN can_chat BOOLEAN NOT NULL DEFAULT false,
+ADD COLUMN can_check_eligibility BOOLEAN NOT NULL DEFAULT false;
+
+-- Only Twitch sign-ins ask for chat or eligibility, so those sessions hold Twitch tokens
+UPDATE user_identities
+SET
+    access_token = sessions.access_token,
+    refresh_token = sessions.refresh_token,
+    expiry = sessions.expiry,
+    can_chat = sessions.can_chat,
+    can_check_eligibility = sessions.can_check_eligibility
+FROM (
+    SELECT DISTINCT ON (user_id) *
+    FROM session_auths
+    WHERE can_chat = true OR can_check_eligibility = true
+    ORDER BY user_id, created_at DESC
+) AS sessions
+WHERE sessions.user_id = user_identities.user_id AND user_identities.provider = 'twitch';
@@ -0,0 +1,11 @@
+DROP INDEX idx_user_cohosts_host_user_id_cohost_user_id;
+CREATE UNIQUE INDEX idx_user_cohosts_host_user_id_twitch_login ON user_cohosts(host_user_id, twitch_login);
+
+ALTER TABLE user_cohosts
+DROP COLUMN cohost_user_id;
+
+DROP INDEX idx_game_cohosts_game_code_cohost_user_id;
+CREATE UNIQUE INDEX idx_game_cohosts_game_code_twitch_login ON game_cohosts(game_code, twitch_login);
+
+ALTER TABLE game_cohosts
+DROP COLUMN cohost_user_id;
@@ -0,0 +1,34 @@
+-- Co-hosts are held by account rather than login, so a rename or someone new signing in
+-- under an old login doesn't carry host rights along. Logins nobody had signed in with
+-- never granted anything and are dropped
+ALTER TABLE game_cohosts
+ADD COLUMN cohost_user_id VARCHAR(128);
+
+UPDATE game_cohosts
+SET cohost_user_id = users.user_id
+FROM users
+WHERE users.twitch_login = game_cohosts.twitch_login;
+
+DELETE FROM game_cohosts WHERE cohost_user_id IS NULL;
+
+ALTER TABLE game_cohosts
+ALTER COLUMN cohost_user_id SET NOT NULL;
+
+DROP INDEX idx_game_cohosts_game_code_twitch_login;
+CREATE UNIQUE INDEX idx_game_cohosts_game_code_cohost_user_id ON game_cohosts(game_code, cohost_user_id);
+
+ALTER TABLE user_cohosts
+ADD COLUMN cohost_user_id VARCHAR(128);
+
+UPDATE user_cohosts
+SET cohost_user_id = users.user_id
+FROM users
+WHERE users.twitch_login = user_cohosts.twitch_login;
+
+DELETE FROM user_cohosts WHERE cohost_user_id IS NULL;
+
+ALTER TABLE user_cohosts
+ALTER COLUMN cohost_user_id SET NOT NULL;
+
+DROP INDEX idx_user_cohosts_host_user_id_twitch_login;
+CREATE UNIQUE INDEX idx_user_cohosts_host_user_id_cohost_user_id ON user_cohosts(host_user_id, cohost_user_id);
@@ -0,0 +1,2 @@
+ALTER TABLE giveaway_draws
+DROP COLUMN method;
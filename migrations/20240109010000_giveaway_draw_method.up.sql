@@ -0,0 +1,3 @@
+-- How the seed picked the winners, so draws keep verifying the way they were made
+ALTER TABLE giveaway_draws
+ADD COLUMN method VARCHAR(128) NOT NULL DEFAULT 'CHACHA8_SHUFFLE';
@@ -0,0 +1,580 @@
+use crate::{
+    config::OidcConfig,
+    models::{PROVIDER_OIDC, PROVIDER_TWITCH},
+    prelude::*,
+};
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use nanoid::nanoid;
+use reqwest::Url;
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use twitch_api::helix::users::GetUsersRequest;
+use twitch_oauth2::{ClientId, ClientSecret, RefreshToken, Scope, TwitchToken, UserTokenBuilder};
+
+pub type AuthProviders = HashMap<&'static str, Arc<dyn AuthProvider>>;
+
+// Providers that don't say how long their token lasts get treated like a Twitch one
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+const OIDC_CSRF_LENGTH: usize = 32;
+// user_identities.subject is a VARCHAR(128)
+const MAX_SUBJECT_LEN: usize = 128;
+
+/// Extra Twitch permissions asked for on connect, other providers only ever sign people in
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AuthScopes {
+    pub with_chat: bool,
+    pub with_eligibility: bool,
+}
+
+/// Who came back from the provider, and the tokens they came back with
+#[derive(Debug, Clone)]
+pub struct AuthLogin {
+    pub subject: String,
+    pub username: String,
+    /// Only Twitch identities have a login, the rest of the app keys co-hosts, invites and blocks on it
+    pub twitch_login: Option<String>,
+
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: Duration,
+}
+
+/// What a refresh grant hands back. Providers that don't rotate refresh tokens send none,
+/// so the old one keeps working
+#[derive(Debug, Clone)]
+pub struct AuthTokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Duration,
+}
+
+/// One way of signing in. The connect handler sends people to `authorize_url` and the
+/// callback hands the code back to `exchange` once the CSRF state has been checked
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn display_name(&self) -> &str;
+    fn client_id(&self) -> &str;
+
+    /// The URL to send the browser to, and the CSRF state it carries
+    async fn authorize_url(&self, scopes: AuthScopes) -> Result<(String, String)>;
+
+    async fn exchange(&self, code: &str, csrf: &str) -> Result<AuthLogin>;
+
+    /// Trades a stored refresh token for a new access token once the old one expires
+    async fn refresh(&self, refresh_token: &str) -> Result<AuthTokens>;
+}
+
+pub fn init_auth_providers(cfg: &Config) -> AuthProviders {
+    let mut providers: AuthProviders = HashMap::new();
+
+    providers.insert(
+        PROVIDER_TWITCH,
+        Arc::new(TwitchAuthProvider::new(
+            cfg.twitch_client_id.clone(),
+            cfg.twitch_client_secret.clone(),
+            cfg.twitch_callback_url.clone(),
+        )),
+    );
+
+    if let Some(oidc) = &cfg.oidc {
+        providers.insert(PROVIDER_OIDC, Arc::new(OidcAuthProvider::new(oidc.clone())));
+    }
+
+    return providers;
+}
+
+pub struct TwitchAuthProvider {
+    client_id: ClientId,
+    client_secret: ClientSecret,
+    callback_url: Url,
+}
+
+impl TwitchAuthProvider {
+    pub fn new(client_id: ClientId, client_secret: ClientSecret, callback_url: Url) -> Self {
+        return Self {
+            client_id,
+            client_secret,
+            callback_url,
+        };
+    }
+
+    fn token_builder(&self) -> UserTokenBuilder {
+        return UserTokenBuilder::new(
+            self.client_id.clone(),
+            self.client_secret.clone(),
+            self.callback_url.clone(),
+        );
+    }
+}
+
+#[async_trait]
+impl AuthProvider for TwitchAuthProvider {
+    fn name(&self) -> &'static str {
+        return PROVIDER_TWITCH;
+    }
+
+    fn display_name(&self) -> &str {
+        return "Twitch";
+    }
+
+    fn client_id(&self) -> &str {
+        return self.client_id.as_str();
+    }
+
+    async fn authorize_url(&self, scopes: AuthScopes) -> Result<(String, String)> {
+        let mut twitch_client = self.token_builder();
+
+        let mut twitch_scopes = vec![];
+        if scopes.with_chat {
+            twitch_scopes.extend([Scope::ChatRead, Scope::ChatEdit]);
+        }
+        // Lets the host's token check followers, subscribers and VIPs when players join
+        if scopes.with_eligibility {
+            twitch_scopes.extend([
+                Scope::ModeratorReadFollowers,
+                Scope::ChannelReadSubscriptions,
+                Scope::ChannelReadVips,
+            ]);
+        }
+
+        if !twitch_scopes.is_empty() {
+            twitch_client = twitch_client.set_scopes(twitch_scopes).force_verify(true);
+        }
+
+        let (url, csrf_token) = twitch_client.generate_url();
+
+        return Ok((url.to_string(), csrf_token.secret().to_string()));
+    }
+
+    async fn exchange(&self, code: &str, csrf: &str) -> Result<AuthLogin> {
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        let mut twitch_client = self.token_builder();
+        twitch_client.set_csrf(csrf.to_string().into());
+
+        let token = twitch_client
+            .get_user_token(&http_client, csrf, code)
+            .await?;
+
+        let logins: &[&twitch_api::types::UserNameRef] = &[&token.login];
+        let request = GetUsersRequest::logins(logins);
+
+        let url = reqwest::Url::parse(&request.get_uri()?.to_string())?;
+
+        use twitch_api::helix::{Request, RequestGet};
+
+        let response = http_client
+            .get(url)
+            .header("Client-ID", token.client_id().as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", token.access_token.secret()),
+            )
+            .send()
+            .await?;
+
+        let response = axum::http::Response::new(response.bytes().await?);
+
+        let uri = request.get_uri()?;
+        let user: twitch_api::helix::Response<_, Vec<twitch_api::helix::users::User>> =
+            GetUsersRequest::parse_response(Some(request), &uri, response)?;
+
+        let username = user
+            .first()
+            .map(|user| user.display_name.to_string())
+            .unwrap_or_else(|| token.login.to_string());
+
+        return Ok(AuthLogin {
+            subject: token.user_id.to_string(),
+            username,
+            twitch_login: Some(token.login.to_string()),
+            access_token: token.access_token.secret().to_string(),
+            refresh_token: token
+                .refresh_token
+                .as_ref()
+                .map(|t| t.secret().to_string())
+                .unwrap_or_default(),
+            expires_in: token.expires_in(),
+        });
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> Result<AuthTokens> {
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        let (access_token, expires_in, refresh_token) =
+            RefreshToken::new(refresh_token.to_string())
+                .refresh_token(&http_client, &self.client_id, &self.client_secret)
+                .await?;
+
+        return Ok(AuthTokens {
+            access_token: access_token.secret().to_string(),
+            refresh_token: refresh_token.map(|t| t.secret().to_string()),
+            expires_in,
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct OidcMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct OidcTokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    name: Option<String>,
+}
+
+/// Authorization code flow against any OpenID Connect issuer. The endpoints come from the
+/// issuer's discovery document, so pointing the issuer URL at a local mock works for testing.
+/// Who signed in is read from the userinfo endpoint with the fresh access token, which saves
+/// verifying ID token signatures
+pub struct OidcAuthProvider {
+    http: reqwest::Client,
+    cfg: OidcConfig,
+    metadata: OnceCell<OidcMetadata>,
+}
+
+impl OidcAuthProvider {
+    pub fn new(cfg: OidcConfig) -> Self {
+        return Self {
+            http: reqwest::Client::new(),
+            cfg,
+            metadata: OnceCell::new(),
+        };
+    }
+
+    // Fetched on first use rather than at startup, so a slow issuer doesn't hold up boot
+    async fn metadata(&self) -> Result<&OidcMetadata> {
+        return self
+            .metadata
+            .get_or_try_init(|| async {
+                let response = self
+                    .http
+                    .get(format!(
+                        "{}/.well-known/openid-configuration",
+                        self.cfg.issuer_url
+                    ))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+
+                let metadata: OidcMetadata = serde_json::from_slice(&response.bytes().await?)?;
+
+                return Ok(metadata) as Result<OidcMetadata>;
+            })
+            .await;
+    }
+}
+
+#[async_trait]
+impl AuthProvider for OidcAuthProvider {
+    fn name(&self) -> &'static str {
+        return PROVIDER_OIDC;
+    }
+
+    fn display_name(&self) -> &str {
+        return &self.cfg.display_name;
+    }
+
+    fn client_id(&self) -> &str {
+        return &self.cfg.client_id;
+    }
+
+    async fn authorize_url(&self, _scopes: AuthScopes) -> Result<(String, String)> {
+        let metadata = self.metadata().await?;
+        let csrf = nanoid!(OIDC_CSRF_LENGTH);
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.cfg.client_id.as_str()),
+                ("redirect_uri", self.cfg.callback_url.as_str()),
+                ("scope", "openid profile"),
+                ("state", csrf.as_str()),
+            ],
+        )?;
+
+        return Ok((url.to_string(), csrf));
+    }
+
+    async fn exchange(&self, code: &str, _csrf: &str) -> Result<AuthLogin> {
+        let metadata = self.metadata().await?;
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.cfg.callback_url.as_str()),
+                ("client_id", self.cfg.client_id.as_str()),
+                ("client_secret", self.cfg.client_secret.secret()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let token: OidcTokenResponse = serde_json::from_slice(&response.bytes().await?)?;
+
+        let response = self
+            .http
+            .get(&metadata.userinfo_endpoint)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let user_info: OidcUserInfo = serde_json::from_slice(&response.bytes().await?)?;
+
+        if user_info.sub.is_empty() || user_info.sub.len() > MAX_SUBJECT_LEN {
+            return Err(anyhow::anyhow!("Identity provider sent an invalid subject"))?;
+        }
+
+        let username = user_info
+            .preferred_username
+            .or(user_info.name)
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| user_info.sub.clone());
+
+        return Ok(AuthLogin {
+            subject: user_info.sub,
+            username,
+            twitch_login: None,
+            access_token: token.access_token,
+            refresh_token: token.refresh_token.unwrap_or_default(),
+            expires_in: token
+                .expires_in
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TOKEN_TTL),
+        });
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> Result<AuthTokens> {
+        let metadata = self.metadata().await?;
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", self.cfg.client_id.as_str()),
+                ("client_secret", self.cfg.client_secret.secret()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let token: OidcTokenResponse = serde_json::from_slice(&response.bytes().await?)?;
+
+        return Ok(AuthTokens {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_in: token
+                .expires_in
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TOKEN_TTL),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        net::TcpListener,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::{get, post},
+        Form, Json, Router,
+    };
+
+    const CLIENT_ID: &str = "test-client";
+    const CLIENT_SECRET: &str = "test-secret";
+    const CODE: &str = "test-code";
+    const NAMELESS_CODE: &str = "nameless-code";
+    const REFRESH_TOKEN: &str = "test-refresh";
+
+    #[derive(Clone)]
+    struct Issuer {
+        base_url: String,
+        discoveries: Arc<AtomicUsize>,
+    }
+
+    async fn discovery(State(issuer): State<Issuer>) -> Json<serde_json::Value> {
+        issuer.discoveries.fetch_add(1, Ordering::SeqCst);
+
+        return Json(serde_json::json!({
+            "issuer": issuer.base_url,
+            "authorization_endpoint": format!("{}/authorize", issuer.base_url),
+            "token_endpoint": format!("{}/token", issuer.base_url),
+            "userinfo_endpoint": format!("{}/userinfo", issuer.base_url),
+        }));
+    }
+
+    // Hands out one access token per code, so userinfo can tell which sign-in it's answering
+    async fn token(
+        Form(form): Form<HashMap<String, String>>,
+    ) -> std::result::Result<Json<serde_json::Value>, StatusCode> {
+        let field = |name: &str| form.get(name).map(String::as_str);
+
+        if field("client_id") != Some(CLIENT_ID) || field("client_secret") != Some(CLIENT_SECRET) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        let access_token = match (field("grant_type"), field("code"), field("refresh_token")) {
+            (Some("authorization_code"), Some(CODE), _) => "named-access",
+            (Some("authorization_code"), Some(NAMELESS_CODE), _) => "nameless-access",
+            (Some("refresh_token"), _, Some(REFRESH_TOKEN)) => "refreshed-access",
+            _ => return Err(StatusCode::BAD_REQUEST),
+        };
+
+        return Ok(Json(serde_json::json!({
+            "access_token": access_token,
+            "token_type": "Bearer",
+            "refresh_token": REFRESH_TOKEN,
+            "expires_in": 120,
+        })));
+    }
+
+    async fn userinfo(
+        headers: HeaderMap,
+    ) -> std::result::Result<Json<serde_json::Value>, StatusCode> {
+        let bearer = headers
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok());
+
+        return match bearer {
+            Some("Bearer named-access") => Ok(Json(serde_json::json!({
+                "sub": "subject-1",
+                "preferred_username": "Someone",
+            }))),
+            Some("Bearer nameless-access") => Ok(Json(serde_json::json!({
+                "sub": "subject-2",
+                "name": " ",
+            }))),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        };
+    }
+
+    fn serve_issuer() -> (OidcAuthProvider, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let discoveries = Arc::new(AtomicUsize::new(0));
+
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo))
+            .with_state(Issuer {
+                base_url: base_url.clone(),
+                discoveries: discoveries.clone(),
+            });
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        let provider = OidcAuthProvider::new(OidcConfig {
+            issuer_url: base_url,
+            client_id: CLIENT_ID.to_string(),
+            client_secret: ClientSecret::new(CLIENT_SECRET.to_string()),
+            callback_url: Url::parse("http://localhost/auth/oidc/callback").unwrap(),
+            display_name: "Test".to_string(),
+        });
+
+        return (provider, discoveries);
+    }
+
+    #[tokio::test]
+    async fn authorize_url_comes_from_discovery() {
+        let (provider, discoveries) = serve_issuer();
+
+        let (url, csrf) = provider.authorize_url(AuthScopes::default()).await.unwrap();
+        let url = Url::parse(&url).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.path(), "/authorize");
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(
+            params["redirect_uri"],
+            "http://localhost/auth/oidc/callback"
+        );
+        assert_eq!(params["state"], csrf);
+        assert_eq!(csrf.len(), OIDC_CSRF_LENGTH);
+
+        // The discovery document is only fetched once
+        provider.authorize_url(AuthScopes::default()).await.unwrap();
+        assert_eq!(discoveries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn exchange_reads_userinfo() {
+        let (provider, _) = serve_issuer();
+
+        let login = provider.exchange(CODE, "csrf").await.unwrap();
+
+        assert_eq!(login.subject, "subject-1");
+        assert_eq!(login.username, "Someone");
+        assert_eq!(login.twitch_login, None);
+        assert_eq!(login.access_token, "named-access");
+        assert_eq!(login.refresh_token, REFRESH_TOKEN);
+        assert_eq!(login.expires_in, Duration::from_secs(120));
+    }
+
+    #[tokio::test]
+    async fn exchange_falls_back_to_the_subject_for_a_name() {
+        let (provider, _) = serve_issuer();
+
+        let login = provider.exchange(NAMELESS_CODE, "csrf").await.unwrap();
+
+        assert_eq!(login.subject, "subject-2");
+        assert_eq!(login.username, "subject-2");
+    }
+
+    #[tokio::test]
+    async fn exchange_rejects_unknown_codes() {
+        let (provider, _) = serve_issuer();
+
+        assert!(provider.exchange("wrong-code", "csrf").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refresh_trades_the_refresh_token() {
+        let (provider, _) = serve_issuer();
+
+        let tokens = provider.refresh(REFRESH_TOKEN).await.unwrap();
+        assert_eq!(tokens.access_token, "refreshed-access");
+        assert_eq!(tokens.expires_in, Duration::from_secs(120));
+
+        assert!(provider.refresh("revoked").await.is_err());
+    }
+}
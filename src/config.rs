@@ -15,6 +15,9 @@ pub struct Config {
     pub twitch_client_id: ClientId,
     pub twitch_client_secret: ClientSecret,
     pub twitch_callback_url: Url,
+    pub twitch_helix_url: String,
+
+    pub oidc: Option<OidcConfig>,
 
     pub db_connection_url: String,
     pub db_database: String,
@@ -28,6 +31,19 @@ pub struct Config {
 
     pub google_key_json_filepath: Option<String>,
     pub google_key_json: Option<String>,
+
+    pub abandoned_game_after_secs: i64,
+    pub abandoned_game_suppress_chat: bool,
+}
+
+/// A generic OpenID Connect issuer players can sign in with next to Twitch
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: ClientSecret,
+    pub callback_url: Url,
+    pub display_name: String,
 }
 
 pub fn load() -> Result<Config> {
@@ -46,10 +62,31 @@ pub fn load() -> Result<Config> {
         Some(server_port.parse()?)
     };
 
+    // Games nobody has touched in this long get finished by the background sweeper
+    let abandoned_game_hours: i64 = match env::var("ABANDONED_GAME_HOURS") {
+        Ok(hours) if !hours.is_empty() => hours.parse()?,
+        _ => 12,
+    };
+
     let twitch_callback_url = format!("{server_host_uri}/twitch/callback")
         .parse()
         .unwrap();
 
+    // OIDC stays off unless an issuer is set, then it needs its client credentials too
+    let oidc = match env::var("OIDC_ISSUER_URL") {
+        Ok(issuer_url) if !issuer_url.is_empty() => Some(OidcConfig {
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+            client_id: env::var("OIDC_CLIENT_ID")?,
+            client_secret: env::var("OIDC_CLIENT_SECRET")?.into(),
+            callback_url: format!("{server_host_uri}/auth/oidc/callback").parse()?,
+            display_name: env::var("OIDC_DISPLAY_NAME")
+                .ok()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "OpenID".to_string()),
+        }),
+        _ => None,
+    };
+
     return Ok(Config {
         server_protocol,
         server_domain,
@@ -59,6 +96,12 @@ pub fn load() -> Result<Config> {
         twitch_client_id: env::var("TWITCH_CLIENT_ID")?.into(),
         twitch_client_secret: env::var("TWITCH_CLIENT_SECRET")?.into(),
         twitch_callback_url,
+        twitch_helix_url: env::var("TWITCH_HELIX_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| "https://api.twitch.tv/helix".to_string()),
+
+        oidc,
 
         db_connection_url: env::var("DB_CONNECTION_URL")?,
         db_database: env::var("DB_DATABASE")?,
@@ -72,5 +115,10 @@ pub fn load() -> Result<Config> {
 
         google_key_json_filepath: env::var("GOOGLE_KEY_JSON_FILEPATH").ok(),
         google_key_json: env::var("GOOGLE_KEY_JSON").ok(),
+
+        abandoned_game_after_secs: abandoned_game_hours * 60 * 60,
+        abandoned_game_suppress_chat: env::var("ABANDONED_GAME_SUPPRESS_CHAT")
+            .map(|value| value == "true")
+            .unwrap_or(false),
     });
 }
@@ -0,0 +1,109 @@
+use super::{utils, Html};
+
+use crate::{
+    models::{User, UserIdentity, PROVIDER_TWITCH},
+    prelude::*,
+};
+
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get},
+    Router,
+};
+use tower_sessions::Session;
+
+pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
+    return router
+        .route("/account", get(account))
+        .route("/account/identities/:id", delete(delete_identity));
+}
+
+#[derive(Template)]
+#[template(path = "account.html")]
+struct AccountTemplate {
+    user: User,
+    identities: Vec<UserIdentity>,
+    /// (provider, display name) for every provider not linked yet
+    unlinked: Vec<(String, String)>,
+}
+
+async fn account(session: Session, State(state): State<AppState>) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let identities: Vec<UserIdentity> = sqlx::query_as(
+        "SELECT * FROM user_identities WHERE user_id = $1 ORDER BY user_identity_id ASC",
+    )
+    .bind(&user.user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut unlinked: Vec<(String, String)> = state
+        .auth_providers
+        .values()
+        .filter(|provider| {
+            !identities
+                .iter()
+                .any(|identity| identity.provider == provider.name())
+        })
+        .map(|provider| {
+            (
+                provider.name().to_string(),
+                provider.display_name().to_string(),
+            )
+        })
+        .collect();
+    unlinked.sort();
+
+    return Ok(Html(AccountTemplate {
+        user,
+        identities,
+        unlinked,
+    })
+    .into_response());
+}
+
+// The last identity stays, otherwise there'd be no way back into the account
+async fn delete_identity(
+    Path(id): Path<u64>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let id = id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_identities WHERE user_id = $1")
+        .bind(&user.user_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    if count <= 1 {
+        return Err(anyhow::anyhow!("You need at least one way to sign in"))?;
+    }
+
+    let provider: Option<String> = sqlx::query_scalar(
+        "DELETE FROM user_identities WHERE user_identity_id = $1 AND user_id = $2 RETURNING provider",
+    )
+    .bind(&id)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(provider) = provider else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    // Co-hosts, invites and blocks go by Twitch login, so it leaves with the Twitch identity
+    if provider == PROVIDER_TWITCH {
+        sqlx::query("UPDATE users SET twitch_login = '' WHERE user_id = $1")
+            .bind(&user.user_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    return Ok("".into_response());
+}
@@ -0,0 +1,474 @@
+use super::*;
+
+use crate::{
+    auth::{AuthProvider, AuthScopes},
+    models::{CsrfToken, User, UserIdentity, PROVIDER_TWITCH},
+    prelude::*,
+};
+
+use std::{sync::Arc, time::SystemTime};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{self, IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
+    return router
+        .route("/twitch/connect", get(twitch_connect))
+        .route("/twitch/callback", get(twitch_callback))
+        .route("/auth/:provider/connect", get(provider_connect))
+        .route("/auth/:provider/callback", get(provider_callback))
+        .route("/logout", get(logout));
+}
+
+#[derive(Deserialize)]
+struct ConnectParams {
+    redirect: Option<String>,
+    with_chat: Option<bool>,
+    with_eligibility: Option<bool>,
+}
+
+fn find_provider(state: &AppState, provider: &str) -> Result<Arc<dyn AuthProvider>> {
+    return Ok(state
+        .auth_providers
+        .get(provider)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Unknown login provider"))?);
+}
+
+// The Twitch app is registered with these URLs, so they stay as they were
+async fn twitch_connect(
+    params: Query<ConnectParams>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    return connect(PROVIDER_TWITCH, params.0, session, state).await;
+}
+
+async fn twitch_callback(
+    params: Query<AuthCallbackParams>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    return callback(PROVIDER_TWITCH, params.0, session, state).await;
+}
+
+async fn provider_connect(
+    Path(provider): Path<String>,
+    params: Query<ConnectParams>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    return connect(&provider, params.0, session, state).await;
+}
+
+async fn provider_callback(
+    Path(provider): Path<String>,
+    params: Query<AuthCallbackParams>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    return callback(&provider, params.0, session, state).await;
+}
+
+async fn connect(
+    provider: &str,
+    params: ConnectParams,
+    session: Session,
+    state: AppState,
+) -> Result<Response> {
+    if params
+        .redirect
+        .as_ref()
+        .is_some_and(|r| !r.starts_with("/"))
+    {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid redirect value").into_response());
+    }
+
+    let provider = find_provider(&state, provider)?;
+
+    // Chat and eligibility are Twitch permissions, other providers only sign people in
+    let is_twitch = provider.name() == PROVIDER_TWITCH;
+    let scopes = AuthScopes {
+        with_chat: is_twitch && matches!(params.with_chat, Some(true)),
+        with_eligibility: is_twitch && matches!(params.with_eligibility, Some(true)),
+    };
+
+    let (url, csrf_token) = provider.authorize_url(scopes).await?;
+
+    let session_id = session.id().0.to_string();
+    session.insert("sid", session_id.clone())?;
+
+    let now_s = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let ttl_s = now_s + 3600; // + 1 hour
+
+    let redirect = params.redirect.unwrap_or("/".to_string());
+
+    sqlx::query(
+        "INSERT INTO csrf_tokens (sid, token, expiry, redirect, with_chat, with_eligibility, provider) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(&session_id)
+    .bind(&csrf_token)
+    .bind(ttl_s as i64)
+    .bind(redirect)
+    .bind(scopes.with_chat)
+    .bind(scopes.with_eligibility)
+    .bind(provider.name())
+    .execute(&state.db)
+    .await?;
+
+    return Ok(response::Redirect::to(&url).into_response());
+}
+
+#[derive(Deserialize)]
+struct AuthCallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+async fn callback(
+    provider: &str,
+    params: AuthCallbackParams,
+    session: Session,
+    state: AppState,
+) -> Result<Response> {
+    if let Some(err) = &params.error {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Error: {err}: {}",
+                params
+                    .error_description
+                    .as_ref()
+                    .unwrap_or(&"unknown".to_string()),
+            ),
+        )
+            .into_response());
+    }
+
+    let (code, auth_state) = match (&params.code, &params.state) {
+        (Some(code), Some(state)) => (code, state),
+        _ => {
+            return Ok((
+                StatusCode::BAD_REQUEST,
+                "Invalid request: missing required params".to_string(),
+            )
+                .into_response());
+        }
+    };
+
+    let provider = find_provider(&state, provider)?;
+
+    let sid = utils::session_id(&session)?;
+
+    let csrf_token: Option<CsrfToken> =
+        sqlx::query_as("SELECT * FROM csrf_tokens WHERE sid = $1 AND provider = $2 AND token = $3")
+            .bind(&sid)
+            .bind(provider.name())
+            .bind(auth_state)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let Some(csrf_token) = csrf_token else {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            "Session token is invalid".to_string(),
+        )
+            .into_response());
+    };
+
+    let now_s = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+
+    if let Some(expiry) = csrf_token.expiry {
+        if expiry as u64 <= now_s {
+            return Ok((StatusCode::BAD_REQUEST, "Request timed out").into_response());
+        }
+    }
+
+    sqlx::query("DELETE FROM csrf_tokens WHERE id = $1")
+        .bind(&(csrf_token.id as i64))
+        .execute(&state.db)
+        .await?;
+
+    let login = provider.exchange(code, &csrf_token.token).await?;
+
+    let identity: Option<UserIdentity> =
+        sqlx::query_as("SELECT * FROM user_identities WHERE provider = $1 AND subject = $2")
+            .bind(provider.name())
+            .bind(&login.subject)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let current_user = utils::find_user(&state, &sid)
+        .await?
+        .map(|user_auth| user_auth.split().0);
+
+    let linked_providers: Vec<String> = match &current_user {
+        Some(current_user) => {
+            sqlx::query_scalar("SELECT provider FROM user_identities WHERE user_id = $1")
+                .bind(&current_user.user_id)
+                .fetch_all(&state.db)
+                .await?
+        }
+        None => vec![],
+    };
+
+    let user_id = link_user_id(
+        provider.name(),
+        &login.subject,
+        identity.as_ref(),
+        current_user.as_ref(),
+        &linked_providers,
+    );
+
+    sqlx::query("INSERT INTO users (user_id, username, twitch_login, provider) VALUES ($1, $2, $3, $4) ON CONFLICT (user_id) DO NOTHING")
+        .bind(&user_id)
+        .bind(&login.username)
+        .bind(login.twitch_login.as_deref().unwrap_or_default())
+        .bind(provider.name())
+        .execute(&state.db)
+        .await?;
+
+    // The account keeps the name from the provider it was made with
+    sqlx::query("UPDATE users SET username = $1 WHERE user_id = $2 AND provider = $3")
+        .bind(&login.username)
+        .bind(&user_id)
+        .bind(provider.name())
+        .execute(&state.db)
+        .await?;
+
+    // An account has at most one Twitch identity, so this only follows renames of its own login.
+    // Whoever held the login before gave it up on Twitch, so co-hosts added by it find this account
+    if let Some(twitch_login) = &login.twitch_login {
+        sqlx::query("UPDATE users SET twitch_login = '' WHERE twitch_login = $1 AND user_id != $2")
+            .bind(twitch_login)
+            .bind(&user_id)
+            .execute(&state.db)
+            .await?;
+
+        sqlx::query("UPDATE users SET twitch_login = $1 WHERE user_id = $2")
+            .bind(twitch_login)
+            .bind(&user_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    let expiry_s = now_s + login.expires_in.as_secs();
+
+    if identity.is_some() {
+        sqlx::query(
+            "UPDATE user_identities SET username = $1 WHERE provider = $2 AND subject = $3",
+        )
+        .bind(&login.username)
+        .bind(provider.name())
+        .bind(&login.subject)
+        .execute(&state.db)
+        .await?;
+
+        // A plain sign-in doesn't swap out a token that can also chat or check eligibility
+        sqlx::query(
+            "UPDATE user_identities SET access_token = $1, refresh_token = $2, expiry = $3, can_chat = $4, can_check_eligibility = $5 WHERE provider = $6 AND subject = $7 AND (can_chat = false OR $4) AND (can_check_eligibility = false OR $5)",
+        )
+        .bind(&login.access_token)
+        .bind(&login.refresh_token)
+        .bind(expiry_s as i64)
+        .bind(csrf_token.with_chat)
+        .bind(csrf_token.with_eligibility)
+        .bind(provider.name())
+        .bind(&login.subject)
+        .execute(&state.db)
+        .await?;
+    } else {
+        sqlx::query("INSERT INTO user_identities (user_id, provider, subject, username, created_at, access_token, refresh_token, expiry, can_chat, can_check_eligibility) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)")
+            .bind(&user_id)
+            .bind(provider.name())
+            .bind(&login.subject)
+            .bind(&login.username)
+            .bind(now_s as i64)
+            .bind(&login.access_token)
+            .bind(&login.refresh_token)
+            .bind(expiry_s as i64)
+            .bind(csrf_token.with_chat)
+            .bind(csrf_token.with_eligibility)
+            .execute(&state.db)
+            .await?;
+    }
+
+    // The session shows what the account can do, whichever provider it signed in with
+    let (can_chat, can_check_eligibility): (bool, bool) = sqlx::query_as(
+        "SELECT COALESCE(bool_or(can_chat), false), COALESCE(bool_or(can_check_eligibility), false) FROM user_identities WHERE user_id = $1",
+    )
+    .bind(&user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    sqlx::query("DELETE FROM session_auths WHERE sid = $1")
+        .bind(&sid)
+        .execute(&state.db)
+        .await?;
+
+    sqlx::query("INSERT INTO session_auths (sid, user_id, client_id, access_token, refresh_token, created_at, expiry, can_chat, can_check_eligibility) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)")
+        .bind(&sid)
+        .bind(&user_id)
+        .bind(provider.client_id())
+        .bind(&login.access_token)
+        .bind(&login.refresh_token)
+        .bind(now_s as i64)
+        .bind(expiry_s as i64)
+        .bind(&can_chat)
+        .bind(&can_check_eligibility)
+        .execute(&state.db)
+        .await?;
+
+    let redirect = csrf_token.redirect.unwrap_or("/".to_string());
+
+    return Ok(response::Redirect::to(&redirect).into_response());
+}
+
+/// Which account a sign-in lands on. A known identity always signs into its own account.
+/// A new one gets linked to whoever is signed in, unless they already have an identity from
+/// that provider, or starts a new account. Twitch accounts keep the Twitch id as their
+/// user_id so Helix lookups and existing rows line up
+fn link_user_id(
+    provider: &str,
+    subject: &str,
+    identity: Option<&UserIdentity>,
+    current_user: Option<&User>,
+    linked_providers: &[String],
+) -> String {
+    // Guests can't keep their nickname account, signing in gives them a real one. A second
+    // Twitch account would take over the login that co-hosting and blocks go by
+    let current_user = current_user.filter(|user| {
+        !user.is_guest() && !linked_providers.iter().any(|linked| linked == provider)
+    });
+
+    return match (identity, current_user) {
+        (Some(identity), _) => identity.user_id.clone(),
+        (None, Some(current_user)) => current_user.user_id.clone(),
+        (None, None) if provider == PROVIDER_TWITCH => subject.to_string(),
+        (None, None) => format!("{provider}:{}", nanoid!()),
+    };
+}
+
+async fn logout(session: Session, State(state): State<AppState>) -> Result<impl IntoResponse> {
+    let sid = session.id().0.to_string();
+
+    sqlx::query("DELETE FROM session_auths WHERE sid = $1")
+        .bind(&sid)
+        .execute(&state.db)
+        .await?;
+
+    return Ok(response::Redirect::to("/"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::models::{GUEST_USER_PREFIX, PROVIDER_OIDC};
+
+    fn user(user_id: &str) -> User {
+        return User {
+            user_id: user_id.to_string(),
+            username: "someone".to_string(),
+            twitch_login: String::new(),
+            provider: PROVIDER_OIDC.to_string(),
+        };
+    }
+
+    fn identity(user_id: &str, provider: &str, subject: &str) -> UserIdentity {
+        return UserIdentity {
+            user_identity_id: 1,
+            user_id: user_id.to_string(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            username: "someone".to_string(),
+            created_at: 0,
+            access_token: String::new(),
+            refresh_token: String::new(),
+            expiry: 0,
+            can_chat: false,
+            can_check_eligibility: false,
+        };
+    }
+
+    #[test]
+    fn known_identity_signs_into_its_own_account() {
+        let known = identity("12345", PROVIDER_TWITCH, "12345");
+
+        let user_id = link_user_id(
+            PROVIDER_TWITCH,
+            "12345",
+            Some(&known),
+            Some(&user("oidc:other")),
+            &[],
+        );
+
+        assert_eq!(user_id, "12345");
+    }
+
+    #[test]
+    fn new_identity_links_to_the_signed_in_account() {
+        let user_id = link_user_id(
+            PROVIDER_TWITCH,
+            "12345",
+            None,
+            Some(&user("oidc:abc")),
+            &[PROVIDER_OIDC.to_string()],
+        );
+
+        assert_eq!(user_id, "oidc:abc");
+    }
+
+    #[test]
+    fn second_identity_from_a_provider_gets_its_own_account() {
+        let user_id = link_user_id(
+            PROVIDER_TWITCH,
+            "67890",
+            None,
+            Some(&user("12345")),
+            &[PROVIDER_TWITCH.to_string()],
+        );
+
+        assert_eq!(user_id, "67890");
+    }
+
+    #[test]
+    fn new_twitch_identity_keeps_the_twitch_id() {
+        let user_id = link_user_id(PROVIDER_TWITCH, "12345", None, None, &[]);
+
+        assert_eq!(user_id, "12345");
+    }
+
+    #[test]
+    fn new_other_identity_starts_a_prefixed_account() {
+        let user_id = link_user_id(PROVIDER_OIDC, "sub-1", None, None, &[]);
+
+        assert!(user_id.starts_with("oidc:"));
+        assert_ne!(user_id, "oidc:sub-1");
+    }
+
+    #[test]
+    fn guests_are_never_linked() {
+        let guest = user(&format!("{GUEST_USER_PREFIX}abc"));
+
+        assert_eq!(
+            link_user_id(PROVIDER_TWITCH, "12345", None, Some(&guest), &[]),
+            "12345"
+        );
+        assert!(link_user_id(PROVIDER_OIDC, "sub-1", None, Some(&guest), &[]).starts_with("oidc:"));
+    }
+}
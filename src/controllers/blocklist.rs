@@ -0,0 +1,117 @@
+use std::time::SystemTime;
+
+use super::{utils, Html};
+
+use crate::{
+    models::{User, UserBlock},
+    prelude::*,
+};
+
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get},
+    Form, Router,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const MAX_BLOCKS: usize = 1000;
+
+pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
+    return router
+        .route("/blocklist", get(blocklist).post(post_block))
+        .route("/blocklist/:id", delete(delete_block));
+}
+
+#[derive(Deserialize)]
+struct BlockForm {
+    login: String,
+}
+
+#[derive(Template)]
+#[template(path = "blocklist.html")]
+struct BlocklistTemplate {
+    user: User,
+    blocks: Vec<UserBlock>,
+}
+
+async fn blocklist(session: Session, State(state): State<AppState>) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let blocks: Vec<UserBlock> = sqlx::query_as(
+        "SELECT * FROM user_blocks WHERE host_user_id = $1 ORDER BY twitch_login ASC",
+    )
+    .bind(&user.user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    return Ok(Html(BlocklistTemplate { user, blocks }).into_response());
+}
+
+// Blocking keeps people out of future joins, anyone already playing is kicked from the game itself
+async fn post_block(
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<BlockForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let login = utils::parse_login(&form.login)?;
+
+    if login == user.twitch_login {
+        return Err(anyhow::anyhow!("You can't block yourself"))?;
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_blocks WHERE host_user_id = $1")
+        .bind(&user.user_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    if count as usize >= MAX_BLOCKS {
+        return Err(anyhow::anyhow!(
+            "Too many blocked logins, the limit is {MAX_BLOCKS}"
+        ))?;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO user_blocks (host_user_id, twitch_login, created_at) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+    )
+    .bind(&user.user_id)
+    .bind(&login)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Redirect::to("/blocklist").into_response());
+}
+
+async fn delete_block(
+    Path(id): Path<u64>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let id = id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let res = sqlx::query("DELETE FROM user_blocks WHERE user_block_id = $1 AND host_user_id = $2")
+        .bind(&id)
+        .bind(&user.user_id)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    return Ok("".into_response());
+}
@@ -0,0 +1,251 @@
+use std::time::SystemTime;
+
+use super::{utils, Html};
+
+use crate::{
+    models::{Game, GameCohost, User, UserCohost, PROVIDER_TWITCH},
+    prelude::*,
+};
+
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get},
+    Form, Router,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const MAX_COHOSTS: usize = 20;
+
+// Rights go to the account behind the login as it is now, so they stay put through renames
+async fn find_cohost_user_id(state: &AppState, login: &str, user: &User) -> Result<String> {
+    let cohost_user_id: Option<String> = sqlx::query_scalar(
+        "SELECT users.user_id FROM users INNER JOIN user_identities ON user_identities.user_id = users.user_id WHERE users.twitch_login = $1 AND user_identities.provider = $2 LIMIT 1",
+    )
+    .bind(login)
+    .bind(PROVIDER_TWITCH)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(cohost_user_id) = cohost_user_id else {
+        return Err(anyhow::anyhow!(
+            "{login} has to sign in with Twitch once before they can co-host"
+        ))?;
+    };
+
+    if cohost_user_id == user.user_id {
+        return Err(anyhow::anyhow!("You already run your own games"))?;
+    }
+
+    return Ok(cohost_user_id);
+}
+
+pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
+    return router
+        .route("/cohosts", get(cohosts).post(post_cohost))
+        .route("/cohosts/:id", delete(delete_cohost))
+        .route(
+            "/games/:game_code/cohosts",
+            get(game_cohosts).post(post_game_cohost),
+        )
+        .route("/games/:game_code/cohosts/:id", delete(delete_game_cohost));
+}
+
+#[derive(Deserialize)]
+struct CohostForm {
+    login: String,
+}
+
+#[derive(Template)]
+#[template(path = "cohosts.html")]
+struct CohostsTemplate {
+    user: User,
+    cohosts: Vec<UserCohost>,
+}
+
+async fn cohosts(session: Session, State(state): State<AppState>) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let cohosts: Vec<UserCohost> = sqlx::query_as(
+        "SELECT * FROM user_cohosts WHERE host_user_id = $1 ORDER BY twitch_login ASC",
+    )
+    .bind(&user.user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    return Ok(Html(CohostsTemplate { user, cohosts }).into_response());
+}
+
+async fn post_cohost(
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<CohostForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let login = utils::parse_login(&form.login)?;
+    let cohost_user_id = find_cohost_user_id(&state, &login, &user).await?;
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM user_cohosts WHERE host_user_id = $1")
+            .bind(&user.user_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    if count as usize >= MAX_COHOSTS {
+        return Err(anyhow::anyhow!(
+            "Too many co-hosts, the limit is {MAX_COHOSTS}"
+        ))?;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO user_cohosts (host_user_id, cohost_user_id, twitch_login, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+    )
+    .bind(&user.user_id)
+    .bind(&cohost_user_id)
+    .bind(&login)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Redirect::to("/cohosts").into_response());
+}
+
+async fn delete_cohost(
+    Path(id): Path<u64>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let id = id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let res =
+        sqlx::query("DELETE FROM user_cohosts WHERE user_cohost_id = $1 AND host_user_id = $2")
+            .bind(&id)
+            .bind(&user.user_id)
+            .execute(&state.db)
+            .await?;
+
+    if res.rows_affected() == 0 {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    return Ok("".into_response());
+}
+
+#[derive(Template)]
+#[template(path = "game-cohosts.html")]
+struct GameCohostsTemplate {
+    user: User,
+    game: Game,
+    cohosts: Vec<GameCohost>,
+    standing_cohosts: Vec<UserCohost>,
+}
+
+// Only the host hands out co-host rights, co-hosts can't add more of their own
+async fn game_cohosts(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    let cohosts: Vec<GameCohost> =
+        sqlx::query_as("SELECT * FROM game_cohosts WHERE game_code = $1 ORDER BY twitch_login ASC")
+            .bind(&game.game_code)
+            .fetch_all(&state.db)
+            .await?;
+
+    let standing_cohosts: Vec<UserCohost> = sqlx::query_as(
+        "SELECT * FROM user_cohosts WHERE host_user_id = $1 ORDER BY twitch_login ASC",
+    )
+    .bind(&user.user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    return Ok(Html(GameCohostsTemplate {
+        user,
+        game,
+        cohosts,
+        standing_cohosts,
+    })
+    .into_response());
+}
+
+async fn post_game_cohost(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<CohostForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+    let login = utils::parse_login(&form.login)?;
+    let cohost_user_id = find_cohost_user_id(&state, &login, &user).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM game_cohosts WHERE game_code = $1")
+        .bind(&game.game_code)
+        .fetch_one(&state.db)
+        .await?;
+
+    if count as usize >= MAX_COHOSTS {
+        return Err(anyhow::anyhow!(
+            "Too many co-hosts, the limit is {MAX_COHOSTS}"
+        ))?;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO game_cohosts (game_code, cohost_user_id, twitch_login, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+    )
+    .bind(&game.game_code)
+    .bind(&cohost_user_id)
+    .bind(&login)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Redirect::to(&format!("/games/{}/cohosts", game.game_code)).into_response());
+}
+
+async fn delete_game_cohost(
+    Path((game_code, id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let id = id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    let res = sqlx::query("DELETE FROM game_cohosts WHERE game_cohost_id = $1 AND game_code = $2")
+        .bind(&id)
+        .bind(&game.game_code)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    return Ok("".into_response());
+}
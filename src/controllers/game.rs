@@ -1,12 +1,23 @@
-use std::{collections::HashSet, time::SystemTime};
+use std::{collections::HashMap, net::SocketAddr, time::SystemTime};
 
-use super::*;
+use super::{utils::HOST_ACCESS, *};
 
 use crate::{
+    helix::EligibilityRule,
     models::{
-        Game, GameItem, GameItemOutcome, GameItemTemplate, GameItemWithGuessCount, GamePlayer,
-        GameTemplate, GameWithHostedSummary, GameWithJoinedSummary, PlayerGuess, User,
-        GAME_STATUS_ACTIVE, GAME_STATUS_FINISHED,
+        draw_winners, is_guest_id, roll_drop, roll_seed_hash, Game, GameBanWithUser,
+        GameEventWithActor, GameItem, GameItemOutcome, GameItemTemplate, GameItemWithGuessCount,
+        GameJoinRequestWithUser, GamePlayer, GamePlayerStanding, GamePlayerTiming,
+        GameRoundWithItems, GameStatus, GameTemplate, GameWithHostedSummary, GameWithJoinedSummary,
+        GiveawayDraw, PlayerGuess, PointAdjustmentWithUsers, ResolvedGuess, User, UserIdentity,
+        EVENT_SOURCE_HOST, EVENT_SOURCE_PLAYER, FINISHED_REASON_ABANDONED, FINISHED_REASON_HOST,
+        FINISHED_REASON_SUDDEN_DEATH, GIVEAWAY_METHOD_SHA256_PICKS, GIVEAWAY_POOL_SCORERS,
+        GIVEAWAY_POOL_WINNERS, GUEST_USER_PREFIX, JOIN_REQUEST_APPROVED, JOIN_REQUEST_DENIED,
+        JOIN_REQUEST_PENDING, LATE_JOIN_AFTER_DROPS, LATE_JOIN_FIRST_LOCK, PROVIDER_GUEST,
+        PROVIDER_OIDC, PROVIDER_TWITCH, ROUND_CLOSED_BY_CHOOSE, ROUND_CLOSED_BY_CLEAR,
+        ROUND_CLOSED_BY_FINISH, ROUND_CLOSED_BY_ROLL, ROUND_STATUS_LOCKED, ROUND_STATUS_OPEN,
+        ROUND_STATUS_RESOLVED, SPEED_BONUS_DECAY, SPEED_BONUS_FIRST_N, TIE_BREAK_CHANGES,
+        TIE_BREAK_RANDOM, TIE_BREAK_SUDDEN_DEATH, TIE_BREAK_TIME,
     },
     prelude::*,
     pubsub::{HostAction, HostActionType, PlayerAction, PlayerActionType},
@@ -15,15 +26,18 @@ use crate::{
 
 use askama::Template;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
+    http::header,
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse, Redirect, Response,
     },
     routing::{get, post, put},
-    Form, Router,
+    Form, Json, Router,
 };
-use serde::Deserialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::PgExecutor;
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt as _;
@@ -32,17 +46,59 @@ use tower_sessions::Session;
 pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
     return router
         .route("/join", get(join))
+        .route("/join/guest", post(post_join_guest))
         .route("/games", get(games).post(post_game))
         .route("/games/:game_code", get(game))
+        .route("/games/:game_code/start", post(start_game_now))
         .route("/games/:game_code/finish", post(finish_game))
+        .route("/games/:game_code/history", get(game_history))
+        .route("/games/:game_code/standings", get(game_standings))
+        .route("/games/:game_code/players", get(game_players))
+        .route("/games/:game_code/events", get(game_events))
+        .route("/games/:game_code/events.json", get(game_events_json))
+        .route("/games/:game_code/replay", get(game_replay))
+        .route("/games/:game_code/replay/:step", get(game_replay_step))
+        .route(
+            "/games/:game_code/players/:game_player_id/kick",
+            post(kick_player),
+        )
+        .route(
+            "/games/:game_code/players/:game_player_id/adjust",
+            post(adjust_points),
+        )
+        .route(
+            "/games/:game_code/bans/:game_ban_id",
+            axum::routing::delete(delete_game_ban),
+        )
+        .route("/games/:game_code/giveaways", post(post_giveaway))
+        .route(
+            "/games/:game_code/giveaways/:giveaway_draw_id",
+            get(giveaway),
+        )
         .route("/games/:game_code/x/redirect", get(game_x_redirect))
         .route("/games/:game_code/x/board", get(game_x_board))
+        .route(
+            "/games/:game_code/x/join-requests",
+            get(game_x_join_requests),
+        )
+        .route(
+            "/games/:game_code/x/join-requests/:game_join_request_id/approve",
+            put(game_x_approve_join_request),
+        )
+        .route(
+            "/games/:game_code/x/join-requests/:game_join_request_id/deny",
+            put(game_x_deny_join_request),
+        )
         .route("/games/:game_code/x/lock", put(game_x_lock))
         .route("/games/:game_code/x/unlock", put(game_x_unlock))
+        .route("/games/:game_code/x/pause", put(game_x_pause))
+        .route("/games/:game_code/x/resume", put(game_x_resume))
         .route(
             "/games/:game_code/x/clear-guesses",
             put(game_x_clear_guesses),
         )
+        .route("/games/:game_code/x/choose-items", put(game_x_choose_items))
+        .route("/games/:game_code/x/roll", put(game_x_roll))
         .route(
             "/games/:game_code/items/:game_item_id/x/enable",
             put(game_x_enable_item),
@@ -63,15 +119,98 @@ pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
         .route("/games/:game_code/sse/player", get(player_sse));
 }
 
+// Running games first, then ones still to start, finished games last
+fn game_status_order() -> String {
+    return format!(
+        "CASE games.status WHEN '{}' THEN 0 WHEN '{}' THEN 1 WHEN '{}' THEN 2 ELSE 3 END",
+        GameStatus::Active.as_str(),
+        GameStatus::Paused.as_str(),
+        GameStatus::Lobby.as_str(),
+    );
+}
+
+async fn can_host(state: &AppState, game: &Game, user: &User) -> Result<bool> {
+    if game.user_id == user.user_id {
+        return Ok(true);
+    }
+
+    let found: Option<i32> = sqlx::query_scalar(&format!(
+        "SELECT 1 FROM games WHERE game_code = $1 AND {HOST_ACCESS} LIMIT 1"
+    ))
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    return Ok(found.is_some());
+}
+
+// Every host action is written to game_events before it goes out, so the log matches what
+// players were sent even if the publish fails
+pub async fn publish_host_action(state: &AppState, action: HostAction) -> Result {
+    let payload = serde_json::to_string(&action.typ)?;
+
+    sqlx::query(
+        "INSERT INTO game_events (game_code, source, event_type, actor, payload, created_at_ms) VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(&action.game_code)
+    .bind(EVENT_SOURCE_HOST)
+    .bind(action.typ.name())
+    .bind(&action.actor)
+    .bind(&payload)
+    .bind(now_ms())
+    .execute(&state.db)
+    .await?;
+
+    state.pubsub.host_actions.publish(action).await?;
+
+    return Ok(());
+}
+
+// Player actions are logged the same way, replays need them for the guess counts
+async fn publish_player_action(state: &AppState, action: PlayerAction) -> Result {
+    let payload = serde_json::to_string(&action.typ)?;
+
+    sqlx::query(
+        "INSERT INTO game_events (game_code, source, event_type, actor, payload, created_at_ms) VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(&action.game_code)
+    .bind(EVENT_SOURCE_PLAYER)
+    .bind(action.typ.name())
+    .bind(&action.user_id)
+    .bind(&payload)
+    .bind(now_ms())
+    .execute(&state.db)
+    .await?;
+
+    state.pubsub.player_actions.publish(action).await?;
+
+    return Ok(());
+}
+
 #[derive(Debug, Deserialize)]
 struct JoinParams {
     code: Option<String>,
+    invite: Option<String>,
 }
 
 #[derive(Template)]
 #[template(path = "join.html")]
 struct JoinTemplate {
     game_code: String,
+    invite: Option<String>,
+    allow_guests: bool,
+    oidc_name: Option<String>,
+}
+
+// Invite tokens come back through redirects, so anything that isn't one is dropped
+fn parse_invite(invite: Option<String>) -> Option<String> {
+    return invite.filter(|invite| {
+        !invite.is_empty()
+            && invite
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    });
 }
 
 async fn join(
@@ -89,28 +228,155 @@ async fn join(
 
     let game_code = game_code.to_lowercase();
 
-    let game = sqlx::query("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
+    let game: Option<Game> = sqlx::query_as("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
         .bind(&game_code)
         .fetch_optional(&state.db)
         .await?;
 
-    if game.is_none() {
+    let Some(game) = game else {
         return Ok(Redirect::to("/").into_response());
-    }
+    };
+
+    let invite = parse_invite(params.invite);
 
     let sid = utils::session_id(&session)?;
     let user_auth = utils::find_user(&state, &sid).await?;
 
     if user_auth.is_some() {
-        return Ok(Redirect::to(&format!("/games/{}", game_code)).into_response());
+        let uri = match &invite {
+            Some(invite) => format!("/games/{game_code}?invite={invite}"),
+            None => format!("/games/{game_code}"),
+        };
+
+        return Ok(Redirect::to(&uri).into_response());
+    }
+
+    return Ok(Html(JoinTemplate {
+        game_code,
+        invite,
+        allow_guests: game.allow_guests,
+        oidc_name: state
+            .auth_providers
+            .get(PROVIDER_OIDC)
+            .map(|provider| provider.display_name().to_string()),
+    })
+    .into_response());
+}
+
+const MAX_NICKNAME_LEN: usize = 25;
+const GUEST_SESSION_TTL_SECS: i64 = 24 * 60 * 60;
+const GUEST_CLIENT_ID: &str = "guest";
+
+#[derive(Deserialize)]
+struct GuestJoinForm {
+    code: String,
+    invite: Option<String>,
+    nickname: String,
+}
+
+fn parse_nickname(nickname: &str) -> Result<String> {
+    let nickname = nickname.trim();
+
+    if nickname.is_empty() || nickname.chars().count() > MAX_NICKNAME_LEN {
+        return Err(anyhow::anyhow!(
+            "Nickname must be between 1 and {MAX_NICKNAME_LEN} characters"
+        ))?;
+    }
+
+    if !nickname
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ' || c == '_' || c == '-')
+    {
+        return Err(anyhow::anyhow!(
+            "Nickname can only have letters, digits, spaces, dashes and underscores"
+        ))?;
+    }
+
+    return Ok(nickname.to_string());
+}
+
+// Guests get a user of their own and a session that expires after GUEST_SESSION_TTL_SECS.
+// The user outlives the session so the games they played keep their name
+async fn post_join_guest(
+    session: Session,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<AppState>,
+    Form(form): Form<GuestJoinForm>,
+) -> Result<Response> {
+    let game_code = form.code.trim().to_lowercase();
+    if game_code.is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+
+    let invite = parse_invite(form.invite);
+    let uri = match &invite {
+        Some(invite) => format!("/games/{game_code}?invite={invite}"),
+        None => format!("/games/{game_code}"),
+    };
+
+    let sid = utils::session_id(&session)?;
+    if utils::find_user(&state, &sid).await?.is_some() {
+        return Ok(Redirect::to(&uri).into_response());
+    }
+
+    let game: Option<Game> = sqlx::query_as(
+        "SELECT * FROM games WHERE game_code = $1 AND allow_guests = true AND status != $2 LIMIT 1",
+    )
+    .bind(&game_code)
+    .bind(GameStatus::Finished)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let nickname = parse_nickname(&form.nickname)?;
+
+    if !state.guest_client_joins.check(&addr.ip().to_string())
+        || !state.guest_joins.check(&game.game_code)
+    {
+        return Err(anyhow::anyhow!(
+            "Too many guests joining right now, try again in a minute"
+        ))?;
     }
 
-    return Ok(Html(JoinTemplate { game_code }).into_response());
+    let user_id = format!("{GUEST_USER_PREFIX}{}", nanoid!());
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO users (user_id, username, twitch_login, provider) VALUES ($1, $2, '', $3)",
+    )
+    .bind(&user_id)
+    .bind(&nickname)
+    .bind(PROVIDER_GUEST)
+    .execute(&state.db)
+    .await?;
+
+    sqlx::query("INSERT INTO session_auths (sid, user_id, client_id, access_token, refresh_token, created_at, expiry, can_chat, can_check_eligibility) VALUES ($1, $2, $3, '', '', $4, $5, false, false)")
+        .bind(&sid)
+        .bind(&user_id)
+        .bind(GUEST_CLIENT_ID)
+        .bind(&now)
+        .bind(&(now + GUEST_SESSION_TTL_SECS))
+        .execute(&state.db)
+        .await?;
+
+    session.insert("sid", sid)?;
+
+    return Ok(Redirect::to(&uri).into_response());
 }
 
+const MAX_START_IN_MINS: i64 = 24 * 60;
+
 #[derive(Debug, Deserialize)]
 struct PostGame {
     template: u64,
+    lobby: Option<String>,
+    start_in: Option<String>,
 }
 
 async fn post_game(
@@ -133,6 +399,27 @@ async fn post_game(
         return Ok((StatusCode::BAD_REQUEST, "Template not found").into_response());
     };
 
+    let start_in = match body.start_in.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(txt) => Some(txt.parse::<i64>()?),
+    };
+
+    if let Some(start_in) = start_in {
+        if !(1..=MAX_START_IN_MINS).contains(&start_in) {
+            return Err(anyhow::anyhow!(
+                "Start time must be between 1 and {MAX_START_IN_MINS} minutes away"
+            ))?;
+        }
+    }
+
+    // A scheduled start always waits in the lobby
+    let status = if body.lobby.is_some() || start_in.is_some() {
+        GameStatus::Lobby
+    } else {
+        GameStatus::Active
+    };
+    let start_at_ms = start_in.map(|start_in| now_ms() + start_in * 60 * 1000);
+
     let game_item_templates: Vec<GameItemTemplate> =
         sqlx::query_as("SELECT * FROM game_item_templates WHERE game_template_id = $1")
             .bind(&game_template.game_template_id)
@@ -168,32 +455,62 @@ async fn post_game(
         .duration_since(SystemTime::UNIX_EPOCH)?
         .as_secs() as i64;
 
-    sqlx::query("INSERT INTO games (user_id, game_code, status, created_at, active_at, name, auto_lock, reward_message, total_reward_message, is_locked) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)")
+    // Games that start unlocked open their first round right away
+    let unlocked_at_ms = (status == GameStatus::Active && !game_template.auto_lock).then(now_ms);
+
+    sqlx::query("INSERT INTO games (user_id, game_code, status, created_at, active_at, name, auto_lock, max_guesses, stream_delay, speed_bonus, speed_bonus_points, speed_bonus_first_n, speed_bonus_decay_secs, tie_break, reward_message, total_reward_message, is_locked, unlocked_at_ms, start_at_ms, eligible_followers, eligible_subscribers, eligible_vips, max_players, late_join, late_join_drops, late_join_catch_up) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26)")
         .bind(&user.user_id)
         .bind(&game_code)
-        .bind(GAME_STATUS_ACTIVE)
+        .bind(status)
         .bind(&now)
         .bind(&now)
         .bind(&game_template.name)
         .bind(&game_template.auto_lock)
+        .bind(&game_template.max_guesses)
+        .bind(&game_template.stream_delay)
+        .bind(&game_template.speed_bonus)
+        .bind(&game_template.speed_bonus_points)
+        .bind(&game_template.speed_bonus_first_n)
+        .bind(&game_template.speed_bonus_decay_secs)
+        .bind(&game_template.tie_break)
         .bind(&game_template.reward_message)
         .bind(&game_template.total_reward_message)
         .bind(&game_template.auto_lock)
+        .bind(&unlocked_at_ms)
+        .bind(&start_at_ms)
+        .bind(&game_template.eligible_followers)
+        .bind(&game_template.eligible_subscribers)
+        .bind(&game_template.eligible_vips)
+        .bind(&game_template.max_players)
+        .bind(&game_template.late_join)
+        .bind(&game_template.late_join_drops)
+        .bind(&game_template.late_join_catch_up)
         .execute(&state.db)
         .await?;
 
-    let game: Game = sqlx::query_as("SELECT * FROM games WHERE game_code = $1")
+    let mut game: Game = sqlx::query_as("SELECT * FROM games WHERE game_code = $1")
         .bind(&game_code)
         .fetch_one(&state.db)
         .await?;
 
     if !game_item_templates.is_empty() {
         let query = format!(
-            "INSERT INTO game_items (game_code, name, image, enabled) VALUES {}",
+            "INSERT INTO game_items (game_code, name, image, enabled, stock, drop_weight) VALUES {}",
             game_item_templates
                 .iter()
                 .enumerate()
-                .map(|(idx, _)| format!("(${}, ${}, ${}, ${})", idx + 1, idx + 2, idx + 3, idx + 4))
+                .map(|(idx, _)| {
+                    let i = idx * 6;
+                    format!(
+                        "(${}, ${}, ${}, ${}, ${}, ${})",
+                        i + 1,
+                        i + 2,
+                        i + 3,
+                        i + 4,
+                        i + 5,
+                        i + 6
+                    )
+                })
                 .collect::<Vec<String>>()
                 .join(",")
         );
@@ -205,12 +522,20 @@ async fn post_game(
                 .bind(&game.game_code)
                 .bind(game_item_template.name)
                 .bind(game_item_template.image)
-                .bind(game_item_template.start_enabled);
+                .bind(game_item_template.start_enabled)
+                .bind(game_item_template.stock)
+                .bind(game_item_template.drop_weight);
         }
 
         q.execute(&state.db).await?;
     }
 
+    // The seed only commits to rolls once the game has items to roll
+    if game.status == GameStatus::Active {
+        open_round(&state.db, &game).await?;
+        refresh_roll_seed(&state.db, &mut game).await?;
+    }
+
     return Ok(Redirect::to(&format!("/games/{}", game.game_code)).into_response());
 }
 
@@ -220,28 +545,32 @@ struct GamesTemplate {
     user: User,
     games_joined: Vec<GameWithJoinedSummary>,
     games_hosted: Vec<GameWithHostedSummary>,
+    abandoned_games: Vec<GameWithHostedSummary>,
 }
 
 async fn games(session: Session, State(state): State<AppState>) -> Result<Response> {
     let session_id = utils::session_id(&session)?;
     let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
-    let games_joined: Vec<GameWithJoinedSummary> = sqlx::query_as(
+    let game_status_order = game_status_order();
+
+    let games_joined: Vec<GameWithJoinedSummary> = sqlx::query_as(&format!(
         r#"
 SELECT *
 FROM games
 LEFT OUTER JOIN (
 	SELECT game_players.game_code AS gp_game_code, COUNT(*) AS players_count
 	FROM game_players
+	WHERE kicked_at IS NULL
 	GROUP BY gp_game_code
 ) AS players_counts ON players_counts.gp_game_code = games.game_code
 LEFT OUTER JOIN (
-	SELECT game_code AS gp2_game_code, points
+	SELECT game_code AS gp2_game_code, points, {CORRECT_GUESSES} AS correct_guesses
 	FROM game_players
-    WHERE user_id = $1
+    WHERE user_id = $1 AND kicked_at IS NULL
 ) AS points ON points.gp2_game_code = games.game_code
 LEFT OUTER JOIN (
-	SELECT game_winners.game_code AS gw_game_code, COUNT(*) AS winners_count, MAX(points) AS winning_points
+	SELECT game_winners.game_code AS gw_game_code, COUNT(*) AS winners_count, MAX({CORRECT_GUESSES}) AS winning_correct_guesses
 	FROM game_winners
 		INNER JOIN game_players ON game_players.game_player_id = game_winners.game_player_id
 	GROUP BY gw_game_code
@@ -258,56 +587,74 @@ LEFT OUTER JOIN (
     FROM users
 ) AS hosts ON hosts.u_user_id = games.user_id
 LEFT OUTER JOIN (
-    SELECT game_code as gio_game_code, COUNT(*) AS total_drops
+    SELECT game_code as gio_game_code, COUNT(DISTINCT group_id) AS total_drops
     FROM game_item_outcomes
     GROUP BY gio_game_code
 ) AS total_drops ON total_drops.gio_game_code = games.game_code
 WHERE games.game_code IN (
 	SELECT game_code
     FROM game_players
-    WHERE user_id = $3
+    WHERE user_id = $3 AND kicked_at IS NULL
 )
-ORDER BY status ASC, created_at DESC
+ORDER BY {game_status_order}, created_at DESC
         "#,
-    )
+    ))
     .bind(&user.user_id)
     .bind(&user.user_id)
     .bind(&user.user_id)
     .fetch_all(&state.db)
     .await?;
 
-    let games_hosted: Vec<GameWithHostedSummary> = sqlx::query_as(
+    let games_hosted: Vec<GameWithHostedSummary> = sqlx::query_as(&format!(
         r#"
 SELECT *
 FROM games
 LEFT OUTER JOIN (
 	SELECT game_players.game_code AS gp_game_code, COUNT(*) AS players_count
 	FROM game_players
+	WHERE kicked_at IS NULL
 	GROUP BY gp_game_code
 ) AS players_counts ON players_counts.gp_game_code = games.game_code
 LEFT OUTER JOIN (
-	SELECT game_winners.game_code AS gw_game_code, COUNT(*) AS winners_count, MAX(points) AS winning_points
+	SELECT game_winners.game_code AS gw_game_code, COUNT(*) AS winners_count, MAX({CORRECT_GUESSES}) AS winning_correct_guesses
 	FROM game_winners
 		INNER JOIN game_players ON game_players.game_player_id = game_winners.game_player_id
 	GROUP BY gw_game_code
 ) AS winners_counts ON winners_counts.gw_game_code = games.game_code
 LEFT OUTER JOIN (
-    SELECT game_code as gio_game_code, COUNT(*) AS total_drops
+    SELECT game_code as gio_game_code, COUNT(DISTINCT group_id) AS total_drops
     FROM game_item_outcomes
     GROUP BY gio_game_code
 ) AS total_drops ON total_drops.gio_game_code = games.game_code
-WHERE games.user_id = $1
-ORDER BY status ASC, created_at DESC
+WHERE games.user_id = $1 OR {HOST_ACCESS}
+ORDER BY {game_status_order}, created_at DESC
         "#,
-    )
+    ))
+    .bind(&user.user_id)
     .bind(&user.user_id)
     .fetch_all(&state.db)
     .await?;
 
+    let abandoned_games: Vec<GameWithHostedSummary> = games_hosted
+        .iter()
+        .filter(|game| game.user_id == user.user_id && !game.finished_notice_seen)
+        .cloned()
+        .collect();
+
+    if !abandoned_games.is_empty() {
+        sqlx::query(
+            "UPDATE games SET finished_notice_seen = true WHERE user_id = $1 AND finished_notice_seen = false",
+        )
+        .bind(&user.user_id)
+        .execute(&state.db)
+        .await?;
+    }
+
     return Ok(Html(GamesTemplate {
         user,
         games_joined,
         games_hosted,
+        abandoned_games,
     })
     .into_response());
 }
@@ -317,7 +664,7 @@ ORDER BY status ASC, created_at DESC
 struct GameAsPlayerTemplate {
     game: Game,
     host: User,
-    guess: Option<PlayerGuess>,
+    guesses: Vec<PlayerGuess>,
     items: Vec<GameItemWithGuessCount>,
     user: User,
     player: GamePlayer,
@@ -325,6 +672,103 @@ struct GameAsPlayerTemplate {
     img_base_uri: String,
 }
 
+impl GameAsPlayerTemplate {
+    fn is_guessed(&self, item_id: &i64) -> bool {
+        return self.guesses.iter().any(|guess| &guess.item_id == item_id);
+    }
+
+    fn guesses_left(&self) -> i32 {
+        return self.game.max_guesses - self.guesses.len() as i32;
+    }
+}
+
+#[derive(Template)]
+#[template(path = "lobby-as-host.html")]
+struct LobbyAsHostTemplate {
+    base_uri: String,
+    game: Game,
+    user: User,
+    players: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "lobby-as-player.html")]
+struct LobbyAsPlayerTemplate {
+    game: Game,
+    host: User,
+    user: User,
+}
+
+// Players join from the lobby but can't guess until the game starts
+async fn lobby(
+    state: &AppState,
+    game: Game,
+    host: User,
+    user: User,
+    invite: Option<&str>,
+) -> Result<Response> {
+    if can_host(state, &game, &user).await? {
+        let players: Vec<String> = sqlx::query_scalar(
+            "SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_players.game_code = $1 AND game_players.kicked_at IS NULL ORDER BY game_players.game_player_id ASC",
+        )
+        .bind(&game.game_code)
+        .fetch_all(&state.db)
+        .await?;
+
+        return Ok(Html(LobbyAsHostTemplate {
+            base_uri: state.cfg.server_host_uri.clone(),
+            game,
+            user,
+            players,
+        })
+        .into_response());
+    }
+
+    let player: Option<GamePlayer> = sqlx::query_as(
+        "SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL",
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    if player.is_none() {
+        if is_banned(state, &game, &user).await? {
+            return Ok(Html(GameBannedTemplate { game, host, user }).into_response());
+        }
+
+        if let Some(response) = check_join(state, &game, &host, &user, invite).await? {
+            return Ok(response);
+        }
+
+        if !add_player(state, &game, &user).await? {
+            return Ok(Redirect::to(&format!("/games/{}", game.game_code)).into_response());
+        }
+
+        let players_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+        )
+        .bind(&game.game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
+
+        publish_player_action(
+            state,
+            PlayerAction {
+                game_code: game.game_code.clone(),
+                user_id: user.user_id.clone(),
+                typ: PlayerActionType::Join {
+                    new_players_count: players_count,
+                },
+            },
+        )
+        .await?;
+    }
+
+    return Ok(Html(LobbyAsPlayerTemplate { game, host, user }).into_response());
+}
+
 #[derive(Template, Clone)]
 #[template(path = "game-as-host.html")]
 struct GameAsHostTemplate {
@@ -350,6 +794,8 @@ struct FinishedGameAsPlayerTemplate {
     player: GamePlayer,
     drops_count: i64,
     is_winner: bool,
+    timings: Vec<GamePlayerTiming>,
+    draws: Vec<GiveawayDraw>,
     img_base_uri: String,
 }
 
@@ -363,19 +809,35 @@ struct FinishedGameAsHostTemplate {
     drops_count: i64,
     leaders: String,
     lead_points: i32,
+    timings: Vec<GamePlayerTiming>,
+    draws: Vec<GiveawayDraw>,
+    rounds: Vec<GameRoundWithItems>,
     img_base_uri: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct GameParams {
+    invite: Option<String>,
+}
+
 async fn game(
     Path(game_code): Path<String>,
+    Query(params): Query<GameParams>,
     session: Session,
     State(state): State<AppState>,
 ) -> Result<Response> {
+    let invite = parse_invite(params.invite);
+
     let session_id = utils::session_id(&session)?;
     let user_auth = utils::find_user(&state, &session_id).await?;
 
     let Some(user_auth) = user_auth else {
-        return Ok(Redirect::to(&format!("/join?code={game_code}")).into_response());
+        let uri = match &invite {
+            Some(invite) => format!("/join?code={game_code}&invite={invite}"),
+            None => format!("/join?code={game_code}"),
+        };
+
+        return Ok(Redirect::to(&uri).into_response());
     };
     let (user, _) = user_auth.split();
 
@@ -393,6 +855,8 @@ async fn game(
         return Ok(Redirect::to("/").into_response());
     };
 
+    let is_host = can_host(&state, &game, &user).await?;
+
     let items: Vec<GameItemWithGuessCount> = sqlx::query_as(
         r#"
 SELECT *
@@ -419,37 +883,97 @@ WHERE
         .fetch_one(&state.db)
         .await?;
 
-    let drops_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
+    let drops_count: i64 = sqlx::query_scalar(DROPS_COUNT_QUERY)
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
+
+    if game.status == GameStatus::Lobby {
+        return lobby(&state, game, host, user, invite.as_deref()).await;
+    }
+
+    if game.status == GameStatus::Finished {
+        let lead_points: Option<Option<i32>> = sqlx::query_scalar(
+            "SELECT MAX(points) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+        )
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?;
+        let lead_points = lead_points.flatten().unwrap_or(0);
+
+        // Speed decides who comes first between players on the same points
+        let timings: Vec<GamePlayerTiming> = sqlx::query_as(&format!(
+            r#"
+SELECT
+    users.user_id,
+    users.username,
+    game_players.points,
+    (
+        SELECT COALESCE(SUM(player_guesses.bonus_points), 0)
+        FROM player_guesses
+        WHERE player_guesses.player_id = game_players.game_player_id
+    ) AS bonus_points,
+    {CORRECT_GUESSES} AS correct_guesses,
+    {TOTAL_RESPONSE_MS} AS total_response_ms
+FROM game_players
+    INNER JOIN users ON users.user_id = game_players.user_id
+WHERE
+    game_players.game_code = $1 AND
+    game_players.kicked_at IS NULL
+ORDER BY {IS_WINNER} DESC, game_players.points DESC, total_response_ms ASC NULLS LAST
+LIMIT 10
+            "#,
+        ))
+        .bind(&game_code)
+        .fetch_all(&state.db)
+        .await?;
+
+        let draws: Vec<GiveawayDraw> = sqlx::query_as(
+            "SELECT * FROM giveaway_draws WHERE game_code = $1 ORDER BY giveaway_draw_id DESC",
+        )
+        .bind(&game_code)
+        .fetch_all(&state.db)
+        .await?;
+
+        if is_host {
+            let players_count: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+            )
             .bind(&game_code)
             .fetch_optional(&state.db)
             .await?
             .unwrap_or(0);
 
-    if game.status != GAME_STATUS_ACTIVE {
-        let lead_points: Option<Option<i32>> =
-            sqlx::query_scalar("SELECT MAX(points) FROM game_players WHERE game_code = $1")
-                .bind(&game_code)
-                .fetch_optional(&state.db)
-                .await?;
-        let lead_points = lead_points.flatten().unwrap_or(0);
-
-        if game.user_id == user.user_id {
-            let players_count: i64 =
-                sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-                    .bind(&game_code)
-                    .fetch_optional(&state.db)
-                    .await?
-                    .unwrap_or(0);
-
             let leaders: Vec<String> =
-        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
+        sqlx::query_scalar("SELECT users.username FROM game_winners INNER JOIN game_players ON game_players.game_player_id = game_winners.game_player_id INNER JOIN users ON users.user_id = game_players.user_id WHERE game_winners.game_code = $1")
             .bind(&game_code)
-            .bind(&lead_points)
             .fetch_all(&state.db)
             .await?;
             let leaders = leaders.join(", ");
 
+            let rounds: Vec<GameRoundWithItems> = sqlx::query_as(
+                r#"
+SELECT
+    game_rounds.*,
+    (
+        SELECT string_agg(game_items.name, ', ')
+        FROM game_item_outcomes
+            INNER JOIN game_items ON game_items.game_item_id = game_item_outcomes.item_id
+        WHERE game_item_outcomes.group_id = game_rounds.outcome_id
+    ) AS item_names,
+    (SELECT username FROM users WHERE users.user_id = game_rounds.resolved_by) AS resolved_by_username
+FROM game_rounds
+WHERE
+    game_rounds.game_code = $1 AND
+    game_rounds.outcome_id IS NOT NULL
+ORDER BY game_rounds.game_round_id ASC
+                "#,
+            )
+            .bind(&game_code)
+            .fetch_all(&state.db)
+            .await?;
+
             return Ok(Html(FinishedGameAsHostTemplate {
                 img_base_uri: state.cfg.r2_bucket_public_url.clone(),
                 game,
@@ -459,12 +983,15 @@ WHERE
                 drops_count,
                 leaders,
                 lead_points,
+                timings,
+                draws,
+                rounds,
             })
             .into_response());
         }
 
         let player: Option<GamePlayer> =
-            sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2")
+            sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL")
                 .bind(&game_code)
                 .bind(&user.user_id)
                 .fetch_optional(&state.db)
@@ -474,15 +1001,25 @@ WHERE
             return Ok(Redirect::to("/").into_response());
         };
 
+        // A tie-break can leave tied players out, so only the recorded winners count
+        let is_winner: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM game_winners WHERE game_player_id = $1)",
+        )
+        .bind(&player.game_player_id)
+        .fetch_one(&state.db)
+        .await?;
+
         return Ok(Html(FinishedGameAsPlayerTemplate {
             img_base_uri: state.cfg.r2_bucket_public_url.clone(),
             game,
             host,
             items,
             user,
-            is_winner: player.points == lead_points,
+            is_winner,
             player,
             drops_count,
+            timings,
+            draws,
         })
         .into_response());
     }
@@ -501,23 +1038,25 @@ WHERE
         });
     }
 
-    if game.user_id == user.user_id {
-        let lead_points: Option<Option<i32>> =
-            sqlx::query_scalar("SELECT MAX(points) FROM game_players WHERE game_code = $1")
-                .bind(&game_code)
-                .fetch_optional(&state.db)
-                .await?;
+    if is_host {
+        let lead_points: Option<Option<i32>> = sqlx::query_scalar(
+            "SELECT MAX(points) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+        )
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?;
         let lead_points = lead_points.flatten().unwrap_or(0);
 
-        let players_count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-                .bind(&game_code)
-                .fetch_optional(&state.db)
-                .await?
-                .unwrap_or(0);
-
-        let leaders: Vec<String> =
-        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
+        let players_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+        )
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
+
+        let leaders: Vec<String> =
+        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2 AND kicked_at IS NULL")
             .bind(&game_code)
             .bind(&lead_points)
             .fetch_all(&state.db)
@@ -541,52 +1080,66 @@ WHERE
         .into_response());
     }
 
-    let player: Option<GamePlayer> =
-        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2")
-            .bind(&game_code)
-            .bind(&user.user_id)
-            .fetch_optional(&state.db)
-            .await?;
+    let game = as_seen_by_players(game);
+
+    let player: Option<GamePlayer> = sqlx::query_as(
+        "SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL",
+    )
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
 
-    let (player, guess) = if let Some(player) = player {
-        let guess: Option<PlayerGuess> = sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL LIMIT 1")
+    let (player, guesses) = if let Some(player) = player {
+        let guesses: Vec<PlayerGuess> = sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL")
             .bind(&game_code)
             .bind(&player.game_player_id)
-            .fetch_optional(&state.db)
+            .fetch_all(&state.db)
             .await?;
 
-        (player, guess)
+        (player, guesses)
     } else {
-        sqlx::query("INSERT INTO game_players (game_code, user_id, points) VALUES ($1, $2, 0)")
-            .bind(&game_code)
-            .bind(&user.user_id)
-            .execute(&state.db)
-            .await?;
+        if is_banned(&state, &game, &user).await? {
+            return Ok(Html(GameBannedTemplate { game, host, user }).into_response());
+        }
+
+        if let Some(response) = check_join(&state, &game, &host, &user, invite.as_deref()).await? {
+            return Ok(response);
+        }
+
+        if !add_player(&state, &game, &user).await? {
+            return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
+        }
+
+        if game.late_join_catch_up {
+            catch_up_late_joiner(&state, &game, &user).await?;
+        }
 
         let jh1 = {
             let game_code = game_code.clone();
             let user_id = user.user_id.clone();
-            let db = state.db.clone();
-            let pubsub = state.pubsub.clone();
+            let state = state.clone();
 
             tokio::spawn(async move {
-                let players_count: i64 =
-                    sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-                        .bind(&game_code)
-                        .fetch_optional(&db)
-                        .await?
-                        .unwrap_or(0);
+                let players_count: i64 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+                )
+                .bind(&game_code)
+                .fetch_optional(&state.db)
+                .await?
+                .unwrap_or(0);
 
-                return Ok(pubsub
-                    .player_actions
-                    .publish(PlayerAction {
+                return Ok(publish_player_action(
+                    &state,
+                    PlayerAction {
                         game_code,
                         user_id,
                         typ: PlayerActionType::Join {
                             new_players_count: players_count,
                         },
-                    })
-                    .await?) as Result;
+                    },
+                )
+                .await?) as Result;
             })
         };
 
@@ -615,7 +1168,7 @@ WHERE
 
             tokio::spawn(async move {
                 return Ok(sqlx::query_as(
-                    "SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2",
+                    "SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL",
                 )
                 .bind(&game_code)
                 .bind(&user_id)
@@ -639,14 +1192,14 @@ WHERE
             r3??
         };
 
-        (gp, None)
+        (gp, vec![])
     };
 
     return Ok(Html(GameAsPlayerTemplate {
         img_base_uri: state.cfg.r2_bucket_public_url.clone(),
         game,
         host,
-        guess,
+        guesses,
         items,
         user,
         player,
@@ -655,11 +1208,33 @@ WHERE
     .into_response());
 }
 
-async fn game_x_redirect(Path(game_code): Path<String>) -> impl IntoResponse {
-    return Redirect::to(&format!("/games/{game_code}"));
+struct HistoryRound {
+    item_names: String,
+    your_guesses: Vec<String>,
+    guess_counts: Vec<(String, i64)>,
+    correct_players: Vec<String>,
+    adjustments: Vec<PointAdjustmentWithUsers>,
+    standings: Vec<HistoryStanding>,
 }
 
-async fn game_x_board(
+#[derive(Clone)]
+struct HistoryStanding {
+    username: String,
+    is_guest: bool,
+    points: i32,
+}
+
+#[derive(Template)]
+#[template(path = "game-history.html")]
+struct GameHistoryTemplate {
+    game: Game,
+    user: User,
+    rounds: Vec<HistoryRound>,
+}
+
+const HISTORY_STANDINGS_SIZE: usize = 5;
+
+async fn game_history(
     Path(game_code): Path<String>,
     session: Session,
     State(state): State<AppState>,
@@ -672,232 +1247,354 @@ async fn game_x_board(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
-        .bind(&game_code)
-        .fetch_optional(&state.db)
-        .await?;
+    let game: Option<Game> =
+        sqlx::query_as("SELECT * FROM games WHERE game_code = $1 AND status = $2 LIMIT 1")
+            .bind(&game_code)
+            .bind(GameStatus::Finished)
+            .fetch_optional(&state.db)
+            .await?;
 
     let Some(game) = game else {
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
-    if game.user_id == user.user_id {
-        let (drops_count, lead_points) = {
-            let jh1 = {
-                let game_code = game_code.clone();
-                let db = state.db.clone();
+    if !can_host(&state, &game, &user).await? {
+        let player: Option<GamePlayer> =
+            sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL")
+                .bind(&game_code)
+                .bind(&user.user_id)
+                .fetch_optional(&state.db)
+                .await?;
 
-                tokio::spawn(async move {
-                    return Ok(sqlx::query_scalar(
-                        "SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1",
-                    )
-                    .bind(&game_code)
-                    .fetch_optional(&db)
-                    .await?
-                    .unwrap_or(0i64)) as Result<i64>;
-                })
-            };
+        if player.is_none() {
+            return Ok(Redirect::to("/").into_response());
+        }
+    }
 
-            let jh2 = {
-                let game_code = game_code.clone();
-                let db = state.db.clone();
+    let outcomes: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+SELECT game_item_outcomes.group_id, string_agg(game_items.name, ', ' ORDER BY game_item_outcomes.outcome_id)
+FROM game_item_outcomes
+    INNER JOIN game_items ON game_items.game_item_id = game_item_outcomes.item_id
+WHERE game_item_outcomes.game_code = $1
+GROUP BY game_item_outcomes.group_id
+ORDER BY game_item_outcomes.group_id ASC
+        "#,
+    )
+    .bind(&game_code)
+    .fetch_all(&state.db)
+    .await?;
 
-                tokio::spawn(async move {
-                    let lead_points: Option<Option<i32>> = sqlx::query_scalar(
-                        "SELECT MAX(points) FROM game_players WHERE game_code = $1",
-                    )
-                    .bind(&game_code)
-                    .fetch_optional(&db)
-                    .await?;
-                    return Ok(lead_points.flatten().unwrap_or(0)) as Result<i32>;
-                })
-            };
+    let guesses: Vec<ResolvedGuess> = sqlx::query_as(
+        r#"
+SELECT
+    player_guesses.outcome_id,
+    player_guesses.player_id,
+    users.username,
+    users.user_id,
+    game_items.name AS item_name,
+    player_guesses.bonus_points,
+    game_item_outcomes.outcome_id IS NOT NULL AS is_correct
+FROM player_guesses
+    INNER JOIN game_players ON game_players.game_player_id = player_guesses.player_id
+    INNER JOIN users ON users.user_id = game_players.user_id
+    INNER JOIN game_items ON game_items.game_item_id = player_guesses.item_id
+    LEFT OUTER JOIN game_item_outcomes ON
+        game_item_outcomes.group_id = player_guesses.outcome_id AND
+        game_item_outcomes.item_id = player_guesses.item_id
+WHERE
+    player_guesses.game_code = $1 AND
+    player_guesses.outcome_id IS NOT NULL
+ORDER BY player_guesses.player_guess_id ASC
+        "#,
+    )
+    .bind(&game_code)
+    .fetch_all(&state.db)
+    .await?;
 
-            let (r1, r2) = tokio::join! { jh1, jh2 };
+    let adjustments: Vec<PointAdjustmentWithUsers> = sqlx::query_as(&format!(
+        "{POINT_ADJUSTMENTS_QUERY} ORDER BY point_adjustments.point_adjustment_id ASC"
+    ))
+    .bind(&game_code)
+    .fetch_all(&state.db)
+    .await?;
 
-            (r1??, r2??)
-        };
+    // Running points per game_player_id, carried from round to round. Names can repeat,
+    // so they're only carried along for display
+    let mut totals: HashMap<i64, HistoryStanding> = HashMap::new();
+    let mut rounds = vec![];
 
-        let (items, players_count, leaders) = {
-            let jh1 = {
-                let game_code = game_code.clone();
-                let db = state.db.clone();
+    // Adjustments made before the first drop are shown with the first round
+    let mut pending_adjustments: Vec<PointAdjustmentWithUsers> = adjustments
+        .iter()
+        .filter(|adjustment| adjustment.after_outcome_id.is_none())
+        .cloned()
+        .collect();
 
-                tokio::spawn(async move {
-                    let items = sqlx::query_as(
-                        r#"
-SELECT *
-FROM game_items
-    LEFT OUTER JOIN (
-        SELECT item_id, COUNT(*) AS guess_count
-        FROM player_guesses
-        WHERE
-            game_code = $1 AND
-            outcome_id IS NULL
-        GROUP BY item_id
-    ) AS guess_counts ON guess_counts.item_id = game_items.game_item_id
-WHERE
-    game_code = $2
-            "#,
-                    )
-                    .bind(&game_code)
-                    .bind(&game_code)
-                    .fetch_all(&db)
-                    .await?;
+    for (group_id, item_names) in outcomes {
+        let round_guesses: Vec<&ResolvedGuess> = guesses
+            .iter()
+            .filter(|guess| guess.outcome_id == group_id)
+            .collect();
 
-                    return Ok(items) as Result<Vec<GameItemWithGuessCount>>;
-                })
-            };
+        let your_guesses = round_guesses
+            .iter()
+            .filter(|guess| guess.user_id == user.user_id)
+            .map(|guess| guess.item_name.clone())
+            .collect();
+
+        let mut guess_counts: Vec<(String, i64)> = vec![];
+        for guess in &round_guesses {
+            match guess_counts
+                .iter_mut()
+                .find(|(name, _)| *name == guess.item_name)
+            {
+                Some((_, count)) => *count += 1,
+                None => guess_counts.push((guess.item_name.clone(), 1)),
+            }
+        }
+        guess_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let mut correct_players: Vec<String> = vec![];
+        let mut correct_player_ids: Vec<i64> = vec![];
+        for guess in &round_guesses {
+            if guess.is_correct || guess.bonus_points > 0 {
+                totals
+                    .entry(guess.player_id)
+                    .or_insert_with(|| HistoryStanding {
+                        username: guess.username.clone(),
+                        is_guest: is_guest_id(&guess.user_id),
+                        points: 0,
+                    })
+                    .points += guess.is_correct as i32 + guess.bonus_points;
+            }
 
-            let jh2 = {
-                let game_code = game_code.clone();
-                let db = state.db.clone();
+            if guess.is_correct && !correct_player_ids.contains(&guess.player_id) {
+                correct_player_ids.push(guess.player_id);
+                correct_players.push(guess.username.clone());
+            }
+        }
 
-                tokio::spawn(async move {
-                    return Ok(sqlx::query_scalar(
-                        "SELECT COUNT(*) FROM game_players WHERE game_code = $1",
-                    )
-                    .bind(&game_code)
-                    .fetch_optional(&db)
-                    .await?
-                    .unwrap_or(0i64)) as Result<i64>;
+        let mut round_adjustments = std::mem::take(&mut pending_adjustments);
+        round_adjustments.extend(
+            adjustments
+                .iter()
+                .filter(|adjustment| adjustment.after_outcome_id == Some(group_id))
+                .cloned(),
+        );
+
+        for adjustment in &round_adjustments {
+            totals
+                .entry(adjustment.player_id)
+                .or_insert_with(|| HistoryStanding {
+                    username: adjustment.username.clone(),
+                    is_guest: adjustment.is_guest(),
+                    points: 0,
                 })
-            };
+                .points += adjustment.points;
+        }
 
-            let jh3 = {
-                let game_code = game_code.clone();
-                let db = state.db.clone();
+        let mut standings: Vec<HistoryStanding> = totals.values().cloned().collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then_with(|| a.username.cmp(&b.username))
+        });
+        standings.truncate(HISTORY_STANDINGS_SIZE);
+
+        rounds.push(HistoryRound {
+            item_names,
+            your_guesses,
+            guess_counts,
+            correct_players,
+            adjustments: round_adjustments,
+            standings,
+        });
+    }
 
-                tokio::spawn(async move {
-                    let leaders: Vec<String> =
-                    sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
-                        .bind(&game_code)
-                        .bind(&lead_points)
-                        .fetch_all(&db)
-                        .await?;
-                    return Ok(leaders.join(", ")) as Result<String>;
-                })
-            };
+    return Ok(Html(GameHistoryTemplate { game, user, rounds }).into_response());
+}
 
-            let (r1, r2, r3) = tokio::join! { jh1, jh2, jh3 };
+const STANDINGS_PAGE_SIZE: i64 = 25;
 
-            (r1??, r2??, r3??)
-        };
+const IS_WINNER: &str = "EXISTS (SELECT 1 FROM game_winners WHERE game_winners.game_player_id = game_players.game_player_id)";
 
-        return Ok(Html(GameAsHostBoardTemplate {
-            img_base_uri: state.cfg.r2_bucket_public_url.clone(),
-            game,
-            any_guesses: items
-                .iter()
-                .any(|item| item.guess_count.map(|c| c > 0).unwrap_or(false)),
-            items,
-            players_count,
-            drops_count,
-            leaders,
-            lead_points,
-        })
-        .into_response());
+// Winners rank ahead of anyone they beat on the tie-break. Until the game finishes
+// there are no winners and points alone decide.
+fn standings_query(filter: &str) -> String {
+    return format!(
+        r#"
+SELECT * FROM (
+    SELECT
+        game_players.game_player_id,
+        game_players.user_id,
+        users.username,
+        game_players.points,
+        RANK() OVER (ORDER BY {IS_WINNER} DESC, game_players.points DESC) AS rank,
+        {CORRECT_GUESSES} AS correct_guesses,
+        (
+            SELECT COALESCE(SUM(point_adjustments.points), 0)
+            FROM point_adjustments
+            WHERE point_adjustments.player_id = game_players.game_player_id
+        ) AS adjusted_points,
+        ROUND(PERCENT_RANK() OVER (ORDER BY {IS_WINNER} ASC, game_players.points ASC) * 100)::INT AS percentile
+    FROM game_players
+        INNER JOIN users ON users.user_id = game_players.user_id
+    WHERE game_players.game_code = $1 AND game_players.kicked_at IS NULL
+) AS standings
+{filter}
+"#
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct StandingsParams {
+    page: Option<i64>,
+}
+
+#[derive(Template)]
+#[template(path = "game-standings.html")]
+struct GameStandingsTemplate {
+    game: Game,
+    user: User,
+    standings: Vec<GamePlayerStanding>,
+    your_standing: Option<GamePlayerStanding>,
+    drops_count: i64,
+    page: i64,
+    pages: i64,
+}
+
+async fn game_standings(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Query(params): Query<StandingsParams>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
     }
+    let game_code = game_code.to_lowercase();
 
-    let game_player: Option<GamePlayer> =
-        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 LIMIT 1")
+    let game: Option<Game> = sqlx::query_as("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let your_standing: Option<GamePlayerStanding> =
+        sqlx::query_as(&standings_query("WHERE user_id = $2"))
             .bind(&game_code)
             .bind(&user.user_id)
             .fetch_optional(&state.db)
             .await?;
 
-    let Some(game_player) = game_player else {
-        return Err(anyhow::anyhow!("Player not found"))?;
-    };
+    if your_standing.is_none() && !can_host(&state, &game, &user).await? {
+        return Ok(Redirect::to("/").into_response());
+    }
 
-    let (guess, items, host, drops_count) = {
-        let jh1 = {
-            let game_code = game_code.clone();
-            let game_player_id = game_player.game_player_id.clone();
-            let db = state.db.clone();
+    let players_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+    )
+    .bind(&game_code)
+    .fetch_optional(&state.db)
+    .await?
+    .unwrap_or(0);
 
-            tokio::spawn(async move {
-                return Ok(sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL LIMIT 1")
-                        .bind(&game_code)
-                        .bind(&game_player_id)
-                        .fetch_optional(&db)
-                        .await?) as Result<Option<PlayerGuess>>;
-            })
-        };
+    let drops_count: i64 = sqlx::query_scalar(DROPS_COUNT_QUERY)
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
 
-        let jh2 = {
-            let game_code = game_code.clone();
-            let db = state.db.clone();
+    let pages = ((players_count + STANDINGS_PAGE_SIZE - 1) / STANDINGS_PAGE_SIZE).max(1);
+    let page = params.page.unwrap_or(1).clamp(1, pages);
 
-            tokio::spawn(async move {
-                return Ok(
-                    sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1")
-                        .bind(&game_code)
-                        .fetch_all(&db)
-                        .await?,
-                ) as Result<Vec<GameItem>>;
-            })
-        };
+    let standings: Vec<GamePlayerStanding> = sqlx::query_as(&standings_query(
+        "ORDER BY rank ASC, username ASC LIMIT $2 OFFSET $3",
+    ))
+    .bind(&game_code)
+    .bind(&STANDINGS_PAGE_SIZE)
+    .bind(&((page - 1) * STANDINGS_PAGE_SIZE))
+    .fetch_all(&state.db)
+    .await?;
 
-        let jh3 = {
-            let user_id = game.user_id.clone();
-            let db = state.db.clone();
+    return Ok(Html(GameStandingsTemplate {
+        game,
+        user,
+        standings,
+        your_standing,
+        drops_count,
+        page,
+        pages,
+    })
+    .into_response());
+}
 
-            tokio::spawn(async move {
-                let host: User = sqlx::query_as("SELECT * FROM users WHERE user_id = $1")
-                    .bind(&user_id)
-                    .fetch_one(&db)
-                    .await?;
-                return Ok(host) as Result<User>;
-            })
-        };
+const GAME_EVENTS_QUERY: &str = r#"
+SELECT
+    game_events.*,
+    (SELECT username FROM users WHERE users.user_id = game_events.actor) AS actor_username
+FROM game_events
+WHERE game_events.game_code = $1
+"#;
+
+async fn find_game_events(
+    state: &AppState,
+    game_code: &str,
+    user: &User,
+) -> Result<(Game, Vec<GameEventWithActor>)> {
+    let game = utils::find_host_access_game(state, game_code, user).await?;
+
+    let events: Vec<GameEventWithActor> = sqlx::query_as(&format!(
+        "{GAME_EVENTS_QUERY} AND game_events.source = $2 ORDER BY game_events.game_event_id ASC"
+    ))
+    .bind(&game.game_code)
+    .bind(EVENT_SOURCE_HOST)
+    .fetch_all(&state.db)
+    .await?;
 
-        let jh4 = {
-            let game_code = game_code.clone();
-            let db = state.db.clone();
+    return Ok((game, events));
+}
 
-            tokio::spawn(async move {
-                return Ok(sqlx::query_scalar(
-                    "SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1",
-                )
-                .bind(&game_code)
-                .fetch_optional(&db)
-                .await?
-                .unwrap_or(0i64)) as Result<i64>;
-            })
-        };
+#[derive(Template)]
+#[template(path = "game-events.html")]
+struct GameEventsTemplate {
+    game: Game,
+    user: User,
+    events: Vec<GameEventWithActor>,
+}
 
-        let (r1, r2, r3, r4) = tokio::join! { jh1, jh2, jh3, jh4 };
+async fn game_events(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
-        (r1??, r2??, r3??, r4??)
-    };
+    let (game, events) = find_game_events(&state, &game_code, &user).await?;
 
-    return Ok(Html(GameAsPlayerBoardTemplate {
-        game,
-        host,
-        guess,
-        items,
-        player: game_player,
-        drops_count,
-        img_base_uri: state.cfg.r2_bucket_public_url.clone(),
-    })
-    .into_response());
+    return Ok(Html(GameEventsTemplate { game, user, events }).into_response());
 }
 
-#[derive(Template, Clone)]
-#[template(path = "game-as-host-board.html")]
-struct GameAsHostBoardTemplate {
-    game: Game,
-    items: Vec<GameItemWithGuessCount>,
-    any_guesses: bool,
-    players_count: i64,
-    drops_count: i64,
-    leaders: String,
-    lead_points: i32,
-    img_base_uri: String,
+#[derive(Serialize)]
+struct GameEventExport {
+    game_event_id: i32,
+    source: String,
+    event_type: String,
+    actor: Option<String>,
+    actor_username: Option<String>,
+    payload: serde_json::Value,
+    created_at_ms: i64,
 }
 
-async fn game_x_lock(
+async fn game_events_json(
     Path(game_code): Path<String>,
     session: Session,
     State(state): State<AppState>,
@@ -905,106 +1602,342 @@ async fn game_x_lock(
     let session_id = utils::session_id(&session)?;
     let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
+    let (game, events) = find_game_events(&state, &game_code, &user).await?;
+
+    let mut export = vec![];
+    for event in events {
+        export.push(GameEventExport {
+            game_event_id: event.game_event_id,
+            source: event.source,
+            event_type: event.event_type,
+            actor: event.actor,
+            actor_username: event.actor_username,
+            payload: serde_json::from_str(&event.payload)?,
+            created_at_ms: event.created_at_ms,
+        });
+    }
+
+    let disposition = format!("attachment; filename=\"{}-events.json\"", game.game_code);
+
+    return Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(export)).into_response());
+}
+
+// Pause before each replay step at 1x, the real gap between events clamped so bursts stay
+// readable and quiet spells don't stall playback
+const REPLAY_MIN_STEP_MS: i64 = 250;
+const REPLAY_MAX_STEP_MS: i64 = 3000;
+
+enum ReplayAction {
+    Host(HostActionType),
+    Player(PlayerActionType),
+}
+
+impl ReplayAction {
+    fn parse(event: &GameEventWithActor) -> Result<ReplayAction> {
+        if event.source == EVENT_SOURCE_PLAYER {
+            return Ok(ReplayAction::Player(serde_json::from_str(&event.payload)?));
+        }
+
+        return Ok(ReplayAction::Host(serde_json::from_str(&event.payload)?));
+    }
+}
+
+struct ReplayBoard {
+    game: Game,
+    items: Vec<GameItemWithGuessCount>,
+    players_count: i64,
+    drops: Vec<String>,
+}
+
+/// Rebuilds the host board as it stood after the first `step` events of a finished game
+fn replay_board(
+    game: &Game,
+    game_items: &[GameItem],
+    events: &[GameEventWithActor],
+    step: usize,
+) -> Result<ReplayBoard> {
+    let actions = events
+        .iter()
+        .map(ReplayAction::parse)
+        .collect::<Result<Vec<ReplayAction>>>()?;
+
+    // Items are stored as they ended, so undo what the game did to them to get the starting board
+    let mut items: Vec<GameItemWithGuessCount> = game_items
+        .iter()
+        .map(|item| {
+            let item_id = item.game_item_id as u64;
+            let mut chosen = 0;
+            let mut first_toggle = None;
+
+            for action in &actions {
+                match action {
+                    ReplayAction::Host(HostActionType::Choose { item_ids })
+                        if item_ids.contains(&item_id) =>
+                    {
+                        chosen += 1;
+                    }
+                    ReplayAction::Host(HostActionType::Enable { item_id: id })
+                        if *id == item_id =>
+                    {
+                        first_toggle.get_or_insert(false);
+                    }
+                    ReplayAction::Host(HostActionType::Disable { item_id: id })
+                        if *id == item_id =>
+                    {
+                        first_toggle.get_or_insert(true);
+                    }
+                    _ => {}
+                }
+            }
+
+            let sold_out = item.stock == Some(0) && chosen > 0;
+
+            return GameItemWithGuessCount {
+                game_item_id: item.game_item_id,
+                game_code: item.game_code.clone(),
+                name: item.name.clone(),
+                image: item.image.clone(),
+                enabled: first_toggle.unwrap_or(item.enabled || sold_out),
+                stock: item.stock.map(|stock| stock + chosen),
+                drop_weight: item.drop_weight,
+                guess_count: Some(0),
+            };
+        })
+        .collect();
+
+    let mut game = game.clone();
+    let started_in_lobby = actions
+        .iter()
+        .any(|action| matches!(action, ReplayAction::Host(HostActionType::Start)));
+    game.status = if started_in_lobby {
+        GameStatus::Lobby
+    } else {
+        GameStatus::Active
+    };
+    game.is_locked = game.auto_lock;
+    game.lock_at_ms = None;
+    game.sudden_death = false;
+
+    let mut players_count = 0;
+    let mut drops = vec![];
+
+    for action in actions.into_iter().take(step) {
+        match action {
+            ReplayAction::Host(typ) => match typ {
+                HostActionType::Start => {
+                    game.status = GameStatus::Active;
+                    game.is_locked = game.auto_lock;
+                }
+                HostActionType::Lock => {
+                    game.is_locked = true;
+                    game.lock_at_ms = None;
+                }
+                HostActionType::Unlock { lock_at_ms } => {
+                    game.is_locked = false;
+                    game.lock_at_ms = lock_at_ms;
+                }
+                HostActionType::ClearGuesses => {
+                    for item in &mut items {
+                        item.guess_count = Some(0);
+                    }
+                }
+                HostActionType::Choose { item_ids } => {
+                    let mut names = vec![];
+                    for item in &mut items {
+                        item.guess_count = Some(0);
+
+                        if !item_ids.contains(&(item.game_item_id as u64)) {
+                            continue;
+                        }
+
+                        names.push(item.name.clone());
+                        item.stock = item.stock.map(|stock| (stock - 1).max(0));
+                        if item.stock == Some(0) {
+                            item.enabled = false;
+                        }
+                    }
+
+                    drops.push(names.join(", "));
+                    game.is_locked = game.auto_lock;
+                    game.lock_at_ms = None;
+                }
+                HostActionType::Enable { item_id } | HostActionType::Disable { item_id } => {
+                    let enabled = matches!(typ, HostActionType::Enable { .. });
+                    for item in &mut items {
+                        if item.game_item_id as u64 == item_id {
+                            item.enabled = enabled;
+                        }
+                    }
+                }
+                HostActionType::SuddenDeath => game.sudden_death = true,
+                HostActionType::Pause => game.status = GameStatus::Paused,
+                HostActionType::Resume => game.status = GameStatus::Active,
+                HostActionType::Finish => game.status = GameStatus::Finished,
+                HostActionType::Kick { .. } | HostActionType::AdjustPoints { .. } => {}
+            },
+            ReplayAction::Player(typ) => match typ {
+                PlayerActionType::Join { new_players_count }
+                | PlayerActionType::Leave { new_players_count } => {
+                    players_count = new_players_count;
+                }
+                PlayerActionType::Guess {
+                    item_id,
+                    new_guess_count,
+                }
+                | PlayerActionType::UndoGuess {
+                    item_id,
+                    new_guess_count,
+                } => {
+                    for item in &mut items {
+                        if item.game_item_id as u64 == item_id {
+                            item.guess_count = Some(new_guess_count);
+                        }
+                    }
+                }
+                PlayerActionType::EnableClearGuesses | PlayerActionType::RequestJoin { .. } => {}
+            },
+        }
+    }
+
+    return Ok(ReplayBoard {
+        game,
+        items,
+        players_count,
+        drops,
+    });
+}
+
+// Replays are read-only, open to the hosts and everyone who played once the game is over
+async fn find_replay(
+    state: &AppState,
+    game_code: &str,
+    user: &User,
+) -> Result<(Game, Vec<GameEventWithActor>)> {
     if game_code.trim().is_empty() {
         return Err(anyhow::anyhow!("Missing game_code"))?;
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
+    let game: Option<Game> =
+        sqlx::query_as("SELECT * FROM games WHERE game_code = $1 AND status = $2 LIMIT 1")
+            .bind(&game_code)
+            .bind(GameStatus::Finished)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let played: Option<i32> = sqlx::query_scalar(
+        "SELECT 1 FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL LIMIT 1",
     )
     .bind(&game_code)
     .bind(&user.user_id)
     .fetch_optional(&state.db)
     .await?;
 
-    let Some(mut game) = game else {
+    if played.is_none() && !can_host(state, &game, user).await? {
         return Err(anyhow::anyhow!("Game not found"))?;
-    };
-
-    if !game.is_locked {
-        sqlx::query("UPDATE games SET is_locked = true WHERE game_code = $1")
-            .bind(&game_code)
-            .execute(&state.db)
-            .await?;
-        game.is_locked = true;
     }
 
-    state
-        .pubsub
-        .host_actions
-        .publish(HostAction {
-            game_code: game_code.clone(),
-            typ: HostActionType::Lock,
-        })
-        .await?;
-
-    let items: Vec<GameItemWithGuessCount> = sqlx::query_as(
-        r#"
-SELECT *
-FROM game_items
-    LEFT OUTER JOIN (
-        SELECT item_id, COUNT(*) AS guess_count
-        FROM player_guesses
-        WHERE
-            game_code = $1 AND
-            outcome_id IS NULL
-        GROUP BY item_id
-    ) AS guess_counts ON guess_counts.item_id = game_items.game_item_id
-WHERE
-    game_code = $2
-            "#,
-    )
-    .bind(&game_code)
+    let events: Vec<GameEventWithActor> = sqlx::query_as(&format!(
+        "{GAME_EVENTS_QUERY} ORDER BY game_events.game_event_id ASC"
+    ))
     .bind(&game_code)
     .fetch_all(&state.db)
     .await?;
 
-    let players_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+    return Ok((game, events));
+}
 
-    let drops_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+#[derive(Template)]
+#[template(path = "game-replay.html")]
+struct GameReplayTemplate {
+    game: Game,
+    user: User,
+    delays: Vec<i64>,
+}
 
-    let lead_points: Option<Option<i32>> =
-        sqlx::query_scalar("SELECT MAX(points) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?;
-    let lead_points = lead_points.flatten().unwrap_or(0);
+async fn game_replay(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
-    let leaders: Vec<String> =
-        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
-            .bind(&game_code)
-            .bind(&lead_points)
+    let (game, events) = find_replay(&state, &game_code, &user).await?;
+
+    let mut delays = vec![];
+    let mut last_at = events.first().map(|event| event.created_at_ms).unwrap_or(0);
+    for event in &events {
+        delays.push((event.created_at_ms - last_at).clamp(REPLAY_MIN_STEP_MS, REPLAY_MAX_STEP_MS));
+        last_at = event.created_at_ms;
+    }
+
+    return Ok(Html(GameReplayTemplate { game, user, delays }).into_response());
+}
+
+#[derive(Template)]
+#[template(path = "game-replay-board.html")]
+struct GameReplayBoardTemplate {
+    game: Game,
+    items: Vec<GameItemWithGuessCount>,
+    players_count: i64,
+    drops: Vec<String>,
+    step: usize,
+    steps: usize,
+    event: Option<GameEventWithActor>,
+    img_base_uri: String,
+}
+
+async fn game_replay_step(
+    Path((game_code, step)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let (game, events) = find_replay(&state, &game_code, &user).await?;
+
+    let game_items: Vec<GameItem> =
+        sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1 ORDER BY game_item_id ASC")
+            .bind(&game.game_code)
             .fetch_all(&state.db)
             .await?;
-    let leaders = leaders.join(", ");
 
-    return Ok(Html(GameAsHostBoardTemplate {
+    let steps = events.len();
+    let step = (step as usize).min(steps);
+    let board = replay_board(&game, &game_items, &events, step)?;
+
+    let event = step.checked_sub(1).map(|idx| events[idx].clone());
+
+    return Ok(Html(GameReplayBoardTemplate {
+        game: board.game,
+        items: board.items,
+        players_count: board.players_count,
+        drops: board.drops,
+        step,
+        steps,
+        event,
         img_base_uri: state.cfg.r2_bucket_public_url.clone(),
-        game,
-        any_guesses: items
-            .iter()
-            .any(|item| item.guess_count.map(|c| c > 0).unwrap_or(false)),
-        items,
-        players_count,
-        drops_count,
-        lead_points,
-        leaders,
     })
     .into_response());
 }
 
-async fn game_x_unlock(
+#[derive(Template)]
+#[template(path = "game-players.html")]
+struct GamePlayersTemplate {
+    game: Game,
+    user: User,
+    players: Vec<GamePlayerStanding>,
+    bans: Vec<GameBanWithUser>,
+    adjustments: Vec<PointAdjustmentWithUsers>,
+}
+
+async fn game_players(
     Path(game_code): Path<String>,
     session: Session,
     State(state): State<AppState>,
@@ -1017,40 +1950,982 @@ async fn game_x_unlock(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
+    let game = utils::find_host_access_game(&state, &game_code, &user).await?;
+
+    let players: Vec<GamePlayerStanding> =
+        sqlx::query_as(&standings_query("ORDER BY rank ASC, username ASC"))
+            .bind(&game_code)
+            .fetch_all(&state.db)
+            .await?;
+
+    let bans: Vec<GameBanWithUser> = sqlx::query_as(
+        "SELECT game_bans.*, users.username FROM game_bans INNER JOIN users ON users.user_id = game_bans.user_id WHERE game_bans.game_code = $1 ORDER BY game_bans.created_at DESC",
     )
     .bind(&game_code)
-    .bind(&user.user_id)
-    .fetch_optional(&state.db)
+    .fetch_all(&state.db)
     .await?;
 
-    let Some(mut game) = game else {
-        return Err(anyhow::anyhow!("Game not found"))?;
-    };
+    let adjustments: Vec<PointAdjustmentWithUsers> = sqlx::query_as(&format!(
+        "{POINT_ADJUSTMENTS_QUERY} ORDER BY point_adjustments.point_adjustment_id DESC"
+    ))
+    .bind(&game_code)
+    .fetch_all(&state.db)
+    .await?;
 
-    if game.is_locked {
-        sqlx::query("UPDATE games SET is_locked = false WHERE game_code = $1")
-            .bind(&game_code)
-            .execute(&state.db)
-            .await?;
-        game.is_locked = false;
-    }
+    return Ok(Html(GamePlayersTemplate {
+        game,
+        user,
+        players,
+        bans,
+        adjustments,
+    })
+    .into_response());
+}
 
-    state
-        .pubsub
-        .host_actions
-        .publish(HostAction {
-            game_code: game_code.clone(),
-            typ: HostActionType::Unlock,
-        })
-        .await?;
+const POINT_ADJUSTMENTS_QUERY: &str = r#"
+SELECT
+    point_adjustments.*,
+    users.user_id,
+    users.username,
+    (SELECT username FROM users AS adjusters WHERE adjusters.user_id = point_adjustments.adjusted_by) AS adjusted_by_username
+FROM point_adjustments
+    INNER JOIN game_players ON game_players.game_player_id = point_adjustments.player_id
+    INNER JOIN users ON users.user_id = game_players.user_id
+WHERE point_adjustments.game_code = $1
+"#;
 
-    let items: Vec<GameItemWithGuessCount> = sqlx::query_as(
-        r#"
-SELECT *
-FROM game_items
-    LEFT OUTER JOIN (
+const MAX_POINT_ADJUSTMENT: i32 = 1000;
+const MAX_ADJUSTMENT_REASON_LEN: usize = 200;
+
+#[derive(Deserialize)]
+struct AdjustPointsForm {
+    points: String,
+    reason: String,
+}
+
+// Every adjustment goes in the ledger, so a player's points always add up to their
+// correct guesses and bonuses plus their adjustments
+async fn adjust_points(
+    Path((game_code, game_player_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<AdjustPointsForm>,
+) -> Result<Response> {
+    let game_player_id = game_player_id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let points: i32 = form.points.trim().parse()?;
+    if points == 0 || !(-MAX_POINT_ADJUSTMENT..=MAX_POINT_ADJUSTMENT).contains(&points) {
+        return Err(anyhow::anyhow!(
+            "Adjustments must be between -{MAX_POINT_ADJUSTMENT} and {MAX_POINT_ADJUSTMENT} points, and not 0"
+        ))?;
+    }
+
+    let reason = form.reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(anyhow::anyhow!("A reason is required"))?;
+    }
+    if reason.chars().count() > MAX_ADJUSTMENT_REASON_LEN {
+        return Err(anyhow::anyhow!(
+            "Reasons can be at most {MAX_ADJUSTMENT_REASON_LEN} characters"
+        ))?;
+    }
+
+    let game = utils::find_host_access_game(&state, &game_code, &user).await?;
+    if game.status != GameStatus::Active && game.status != GameStatus::Paused {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    }
+
+    let player: Option<GamePlayer> = sqlx::query_as(
+        "SELECT * FROM game_players WHERE game_player_id = $1 AND game_code = $2 AND kicked_at IS NULL LIMIT 1",
+    )
+    .bind(&game_player_id)
+    .bind(&game_code)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(player) = player else {
+        return Err(anyhow::anyhow!("Player not found"))?;
+    };
+
+    let after_outcome_id: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(group_id) FROM game_item_outcomes WHERE game_code = $1")
+            .bind(&game_code)
+            .fetch_one(&state.db)
+            .await?;
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO point_adjustments (game_code, player_id, points, reason, adjusted_by, after_outcome_id, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(&game_code)
+    .bind(&player.game_player_id)
+    .bind(&points)
+    .bind(&reason)
+    .bind(&user.user_id)
+    .bind(&after_outcome_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    sqlx::query("UPDATE game_players SET points = points + $1 WHERE game_player_id = $2")
+        .bind(&points)
+        .bind(&player.game_player_id)
+        .execute(&state.db)
+        .await?;
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::AdjustPoints {
+                user_id: player.user_id,
+            },
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return Ok(Redirect::to(&format!("/games/{game_code}/players")).into_response());
+}
+
+const KICK_BAN_GAME: &str = "GAME";
+const KICK_BAN_ACCOUNT: &str = "ACCOUNT";
+
+#[derive(Deserialize)]
+struct KickForm {
+    ban: Option<String>,
+}
+
+// Kicked players lose their spot and open guesses, and come back with no points unless banned.
+// Their past guesses stay on the rounds they were counted in.
+async fn kick_player(
+    Path((game_code, game_player_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<KickForm>,
+) -> Result<Response> {
+    let game_player_id = game_player_id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let ban = form.ban.filter(|ban| !ban.is_empty());
+    if let Some(ban) = &ban {
+        if ban != KICK_BAN_GAME && ban != KICK_BAN_ACCOUNT {
+            return Err(anyhow::anyhow!("Unknown ban: {ban}"))?;
+        }
+    }
+
+    let game = utils::find_host_access_game(&state, &game_code, &user).await?;
+    if game.status == GameStatus::Finished {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    }
+
+    let player: Option<GamePlayer> = sqlx::query_as(
+        "SELECT * FROM game_players WHERE game_player_id = $1 AND game_code = $2 AND kicked_at IS NULL LIMIT 1",
+    )
+    .bind(&game_player_id)
+    .bind(&game_code)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(player) = player else {
+        return Err(anyhow::anyhow!("Player not found"))?;
+    };
+
+    let mut item_ids: Vec<i64> = sqlx::query_scalar(
+        "DELETE FROM player_guesses WHERE player_id = $1 AND outcome_id IS NULL RETURNING item_id",
+    )
+    .bind(&player.game_player_id)
+    .fetch_all(&state.db)
+    .await?;
+    item_ids.sort();
+    item_ids.dedup();
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    // The row stays so the rounds and ledger entries that point at it keep their name,
+    // coming back starts a fresh row
+    sqlx::query("UPDATE game_players SET kicked_at = $1 WHERE game_player_id = $2")
+        .bind(&now)
+        .bind(&player.game_player_id)
+        .execute(&state.db)
+        .await?;
+
+    // Guests and accounts that never linked Twitch have no login to block by,
+    // so blocking one only bans them from this game
+    let player_login: String =
+        sqlx::query_scalar("SELECT twitch_login FROM users WHERE user_id = $1")
+            .bind(&player.user_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    if ban.as_deref() == Some(KICK_BAN_GAME)
+        || (ban.as_deref() == Some(KICK_BAN_ACCOUNT) && player_login.is_empty())
+    {
+        sqlx::query(
+            "INSERT INTO game_bans (game_code, user_id, banned_by, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        )
+        .bind(&game_code)
+        .bind(&player.user_id)
+        .bind(&user.user_id)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+    } else if ban.as_deref() == Some(KICK_BAN_ACCOUNT) {
+        // Blocks always land on the host's own list, even when a co-host does the kicking
+        sqlx::query(
+            "INSERT INTO user_blocks (host_user_id, twitch_login, created_at) SELECT $1, twitch_login, $2 FROM users WHERE user_id = $3 ON CONFLICT DO NOTHING",
+        )
+        .bind(&game.user_id)
+        .bind(&now)
+        .bind(&player.user_id)
+        .execute(&state.db)
+        .await?;
+    }
+
+    let players_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+    )
+    .bind(&game_code)
+    .fetch_optional(&state.db)
+    .await?
+    .unwrap_or(0);
+
+    publish_player_action(
+        &state,
+        PlayerAction {
+            game_code: game_code.clone(),
+            user_id: player.user_id.clone(),
+            typ: PlayerActionType::Leave {
+                new_players_count: players_count,
+            },
+        },
+    )
+    .await?;
+
+    for item_id in item_ids {
+        let new_guess_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM player_guesses WHERE game_code = $1 AND item_id = $2 AND outcome_id IS NULL",
+        )
+        .bind(&game_code)
+        .bind(&item_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        publish_player_action(
+            &state,
+            PlayerAction {
+                game_code: game_code.clone(),
+                user_id: player.user_id.clone(),
+                typ: PlayerActionType::UndoGuess {
+                    item_id: item_id as u64,
+                    new_guess_count: new_guess_count as i32,
+                },
+            },
+        )
+        .await?;
+    }
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::Kick {
+                user_id: player.user_id,
+            },
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return Ok(Redirect::to(&format!("/games/{game_code}/players")).into_response());
+}
+
+async fn delete_game_ban(
+    Path((game_code, game_ban_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let game_ban_id = game_ban_id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    utils::find_host_access_game(&state, &game_code, &user).await?;
+
+    sqlx::query("DELETE FROM game_bans WHERE game_ban_id = $1 AND game_code = $2")
+        .bind(&game_ban_id)
+        .bind(&game_code)
+        .execute(&state.db)
+        .await?;
+
+    return Ok("".into_response());
+}
+
+// Covers both bans from this game and the host's account-wide blocklist
+async fn is_banned(state: &AppState, game: &Game, user: &User) -> Result<bool> {
+    let found: Option<i32> = sqlx::query_scalar(
+        r#"
+SELECT 1
+WHERE
+    EXISTS (SELECT 1 FROM game_bans WHERE game_code = $1 AND user_id = $2) OR
+    EXISTS (SELECT 1 FROM user_blocks WHERE host_user_id = $3 AND twitch_login = $4)
+"#,
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .bind(&game.user_id)
+    .bind(&user.twitch_login)
+    .fetch_optional(&state.db)
+    .await?;
+
+    return Ok(found.is_some());
+}
+
+#[derive(Template)]
+#[template(path = "game-banned.html")]
+struct GameBannedTemplate {
+    game: Game,
+    host: User,
+    user: User,
+}
+
+#[derive(Template)]
+#[template(path = "game-closed.html")]
+struct GameClosedTemplate {
+    game: Game,
+    host: User,
+    user: User,
+    is_full: bool,
+}
+
+#[derive(Template)]
+#[template(path = "game-invite-only.html")]
+struct GameInviteOnlyTemplate {
+    game: Game,
+    host: User,
+    user: User,
+}
+
+#[derive(Template)]
+#[template(path = "game-join-request.html")]
+struct GameJoinRequestTemplate {
+    game: Game,
+    host: User,
+    user: User,
+    denied: bool,
+}
+
+#[derive(Template)]
+#[template(path = "game-ineligible.html")]
+struct GameIneligibleTemplate {
+    game: Game,
+    host: User,
+    user: User,
+    // The host hasn't given us a token that can check, so nobody can be let in yet
+    host_unverified: bool,
+}
+
+async fn find_twitch_id(state: &AppState, user_id: &str) -> Result<Option<String>> {
+    return Ok(sqlx::query_scalar(
+        "SELECT subject FROM user_identities WHERE user_id = $1 AND provider = $2 LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(PROVIDER_TWITCH)
+    .fetch_optional(&state.db)
+    .await?);
+}
+
+/// The token of the host's Twitch identity if it can check eligibility, refreshed first when it
+/// has expired. None when there's no such token or Twitch won't refresh it, which means the host
+/// has to reconnect
+async fn find_eligibility_token(state: &AppState, user_id: &str) -> Result<Option<String>> {
+    let identity: Option<UserIdentity> = sqlx::query_as(
+        "SELECT * FROM user_identities WHERE user_id = $1 AND provider = $2 AND can_check_eligibility = true LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(PROVIDER_TWITCH)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(identity) = identity else {
+        return Ok(None);
+    };
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    if identity.expiry > now {
+        return Ok(Some(identity.access_token));
+    }
+
+    let Some(provider) = state.auth_providers.get(PROVIDER_TWITCH) else {
+        return Ok(None);
+    };
+
+    if identity.refresh_token.is_empty() {
+        return Ok(None);
+    }
+
+    let tokens = match provider.refresh(&identity.refresh_token).await {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("Couldn't refresh the eligibility token of {user_id}: {e}");
+            return Ok(None);
+        }
+    };
+
+    sqlx::query(
+        "UPDATE user_identities SET access_token = $1, refresh_token = $2, expiry = $3 WHERE user_identity_id = $4",
+    )
+    .bind(&tokens.access_token)
+    .bind(tokens.refresh_token.as_ref().unwrap_or(&identity.refresh_token))
+    .bind(&(now + tokens.expires_in.as_secs() as i64))
+    .bind(&identity.user_identity_id)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Some(tokens.access_token));
+}
+
+// Players need to match one of the game's rules, asked of Helix with the host's token
+async fn check_eligibility(
+    state: &AppState,
+    game: &Game,
+    host: &User,
+    user: &User,
+) -> Result<Option<Response>> {
+    let rules: Vec<EligibilityRule> = [
+        (game.eligible_followers, EligibilityRule::Follower),
+        (game.eligible_subscribers, EligibilityRule::Subscriber),
+        (game.eligible_vips, EligibilityRule::Vip),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, rule)| rule)
+    .collect();
+
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    // Helix wants Twitch ids, which accounts made through another provider only have once
+    // they've linked Twitch. Guests never do, so they have nothing to follow or subscribe with
+    let Some(player_twitch_id) = find_twitch_id(state, &user.user_id).await? else {
+        return Ok(Some(
+            Html(GameIneligibleTemplate {
+                game: game.clone(),
+                host: host.clone(),
+                user: user.clone(),
+                host_unverified: false,
+            })
+            .into_response(),
+        ));
+    };
+
+    let access_token = find_eligibility_token(state, &game.user_id).await?;
+    let host_twitch_id = find_twitch_id(state, &game.user_id).await?;
+
+    let host_unverified = access_token.is_none() || host_twitch_id.is_none();
+
+    if let (Some(access_token), Some(host_twitch_id)) = (access_token, host_twitch_id) {
+        for rule in rules {
+            if state
+                .helix
+                .check(&access_token, &host_twitch_id, &player_twitch_id, rule)
+                .await?
+            {
+                return Ok(None);
+            }
+        }
+    }
+
+    return Ok(Some(
+        Html(GameIneligibleTemplate {
+            game: game.clone(),
+            host: host.clone(),
+            user: user.clone(),
+            host_unverified,
+        })
+        .into_response(),
+    ));
+}
+
+// A drop is one outcome group, however many items it rolled
+const DROPS_COUNT_QUERY: &str =
+    "SELECT COUNT(DISTINCT group_id) FROM game_item_outcomes WHERE game_code = $1";
+
+// Drops `game_players` guessed right, for queries with that table in scope. Unlike
+// `points` this leaves out speed bonuses, adjustments and catch-up points.
+const CORRECT_GUESSES: &str = r#"(
+    SELECT COUNT(DISTINCT player_guesses.outcome_id)
+    FROM player_guesses
+        INNER JOIN game_item_outcomes ON
+            game_item_outcomes.group_id = player_guesses.outcome_id AND
+            game_item_outcomes.item_id = player_guesses.item_id
+    WHERE player_guesses.player_id = game_players.game_player_id
+)"#;
+
+// Time from unlock to each correct guess, summed. The time tie-break goes to the
+// lowest total, so anything listing players by speed should order on this too.
+const TOTAL_RESPONSE_MS: &str = r#"(
+    SELECT SUM(player_guesses.response_ms)
+    FROM player_guesses
+        INNER JOIN game_item_outcomes ON
+            game_item_outcomes.group_id = player_guesses.outcome_id AND
+            game_item_outcomes.item_id = player_guesses.item_id
+    WHERE player_guesses.player_id = game_players.game_player_id
+)::BIGINT"#;
+
+async fn count_drops(state: &AppState, game_code: &str) -> Result<i64> {
+    return Ok(sqlx::query_scalar(DROPS_COUNT_QUERY)
+        .bind(game_code)
+        .fetch_one(&state.db)
+        .await?);
+}
+
+/// Rejects new players once the game is full or past its late-join cutoff
+async fn check_capacity(
+    state: &AppState,
+    game: &Game,
+    host: &User,
+    user: &User,
+) -> Result<Option<Response>> {
+    let closed = |is_full| {
+        return Some(
+            Html(GameClosedTemplate {
+                game: game.clone(),
+                host: host.clone(),
+                user: user.clone(),
+                is_full,
+            })
+            .into_response(),
+        );
+    };
+
+    if let Some(max_players) = game.max_players {
+        let players_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+        )
+        .bind(&game.game_code)
+        .fetch_one(&state.db)
+        .await?;
+
+        if players_count >= max_players as i64 {
+            return Ok(closed(true));
+        }
+    }
+
+    if game.status != GameStatus::Active && game.status != GameStatus::Paused {
+        return Ok(None);
+    }
+
+    if game.late_join == LATE_JOIN_AFTER_DROPS {
+        if count_drops(state, &game.game_code).await? >= game.late_join_drops as i64 {
+            return Ok(closed(false));
+        }
+    } else if game.late_join == LATE_JOIN_FIRST_LOCK {
+        let locked: Option<i32> = sqlx::query_scalar(
+            "SELECT 1 FROM game_rounds WHERE game_code = $1 AND locked_at_ms IS NOT NULL LIMIT 1",
+        )
+        .bind(&game.game_code)
+        .fetch_optional(&state.db)
+        .await?;
+
+        if locked.is_some() || count_drops(state, &game.game_code).await? > 0 {
+            return Ok(closed(false));
+        }
+    }
+
+    return Ok(None);
+}
+
+/// Adds the user as a player, false when the game filled up or they joined from another tab
+/// since check_capacity. The game row is locked so concurrent joins are counted one at a time
+async fn add_player(state: &AppState, game: &Game, user: &User) -> Result<bool> {
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query("SELECT 1 FROM games WHERE game_code = $1 FOR UPDATE")
+        .bind(&game.game_code)
+        .execute(&mut *tx)
+        .await?;
+
+    let inserted = sqlx::query(
+        r#"
+INSERT INTO game_players (game_code, user_id, points)
+SELECT $1, $2, 0
+WHERE
+    NOT EXISTS (SELECT 1 FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL) AND
+    ($3::INT IS NULL OR (SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL) < $3)
+        "#,
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .bind(&game.max_players)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+
+    return Ok(inserted > 0);
+}
+
+/// Starts a late joiner on the lowest score in the game, recorded in the points ledger
+async fn catch_up_late_joiner(state: &AppState, game: &Game, user: &User) -> Result {
+    if count_drops(state, &game.game_code).await? == 0 {
+        return Ok(());
+    }
+
+    let player: GamePlayer = sqlx::query_as(
+        "SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL",
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let min_points: Option<i32> = sqlx::query_scalar(
+        "SELECT MIN(points) FROM game_players WHERE game_code = $1 AND user_id != $2 AND kicked_at IS NULL",
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let points = min_points.unwrap_or(0);
+    if points <= 0 {
+        return Ok(());
+    }
+
+    let after_outcome_id: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(group_id) FROM game_item_outcomes WHERE game_code = $1")
+            .bind(&game.game_code)
+            .fetch_one(&state.db)
+            .await?;
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO point_adjustments (game_code, player_id, points, reason, adjusted_by, after_outcome_id, created_at) VALUES ($1, $2, $3, $4, NULL, $5, $6)",
+    )
+    .bind(&game.game_code)
+    .bind(&player.game_player_id)
+    .bind(&points)
+    .bind("Late-join catch-up")
+    .bind(&after_outcome_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    sqlx::query("UPDATE game_players SET points = points + $1 WHERE game_player_id = $2")
+        .bind(&points)
+        .bind(&player.game_player_id)
+        .execute(&state.db)
+        .await?;
+
+    return Ok(());
+}
+
+/// Stops new players at the door of full, follower/sub-only, invite-only and approval games,
+/// None once they're clear to join
+async fn check_join(
+    state: &AppState,
+    game: &Game,
+    host: &User,
+    user: &User,
+    invite: Option<&str>,
+) -> Result<Option<Response>> {
+    // The host can turn guests off after some have already joined through the join page
+    if user.is_guest() && !game.allow_guests {
+        return Ok(Some(
+            Html(GameClosedTemplate {
+                game: game.clone(),
+                host: host.clone(),
+                user: user.clone(),
+                is_full: false,
+            })
+            .into_response(),
+        ));
+    }
+
+    if let Some(response) = check_capacity(state, game, host, user).await? {
+        return Ok(Some(response));
+    }
+
+    if let Some(response) = check_eligibility(state, game, host, user).await? {
+        return Ok(Some(response));
+    }
+
+    if game.invite_only && (invite.is_none() || invite != game.invite_token.as_deref()) {
+        let invited: Option<i32> = sqlx::query_scalar(
+            "SELECT 1 FROM game_invites WHERE game_code = $1 AND twitch_login = $2 LIMIT 1",
+        )
+        .bind(&game.game_code)
+        .bind(&user.twitch_login)
+        .fetch_optional(&state.db)
+        .await?;
+
+        if invited.is_none() {
+            return Ok(Some(
+                Html(GameInviteOnlyTemplate {
+                    game: game.clone(),
+                    host: host.clone(),
+                    user: user.clone(),
+                })
+                .into_response(),
+            ));
+        }
+    }
+
+    if !game.require_approval {
+        return Ok(None);
+    }
+
+    let status: Option<String> = sqlx::query_scalar(
+        "SELECT status FROM game_join_requests WHERE game_code = $1 AND user_id = $2 LIMIT 1",
+    )
+    .bind(&game.game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    if status.as_deref() == Some(JOIN_REQUEST_APPROVED) {
+        return Ok(None);
+    }
+
+    if status.is_none() {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs() as i64;
+
+        sqlx::query(
+            "INSERT INTO game_join_requests (game_code, user_id, status, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        )
+        .bind(&game.game_code)
+        .bind(&user.user_id)
+        .bind(JOIN_REQUEST_PENDING)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        let pending_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM game_join_requests WHERE game_code = $1 AND status = $2",
+        )
+        .bind(&game.game_code)
+        .bind(JOIN_REQUEST_PENDING)
+        .fetch_one(&state.db)
+        .await?;
+
+        publish_player_action(
+            state,
+            PlayerAction {
+                game_code: game.game_code.clone(),
+                user_id: user.user_id.clone(),
+                typ: PlayerActionType::RequestJoin { pending_count },
+            },
+        )
+        .await?;
+    }
+
+    return Ok(Some(
+        Html(GameJoinRequestTemplate {
+            game: game.clone(),
+            host: host.clone(),
+            user: user.clone(),
+            denied: status.as_deref() == Some(JOIN_REQUEST_DENIED),
+        })
+        .into_response(),
+    ));
+}
+
+#[derive(Template)]
+#[template(path = "game-join-requests.html")]
+struct GameJoinRequestsTemplate {
+    game: Game,
+    requests: Vec<GameJoinRequestWithUser>,
+}
+
+async fn render_join_requests(state: &AppState, game: Game) -> Result<Response> {
+    let requests: Vec<GameJoinRequestWithUser> = sqlx::query_as(
+        "SELECT game_join_requests.*, users.username FROM game_join_requests INNER JOIN users ON users.user_id = game_join_requests.user_id WHERE game_join_requests.game_code = $1 AND game_join_requests.status = $2 ORDER BY game_join_requests.game_join_request_id ASC",
+    )
+    .bind(&game.game_code)
+    .bind(JOIN_REQUEST_PENDING)
+    .fetch_all(&state.db)
+    .await?;
+
+    return Ok(Html(GameJoinRequestsTemplate { game, requests }).into_response());
+}
+
+async fn game_x_join_requests(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_host_access_game(&state, &game_code, &user).await?;
+
+    return render_join_requests(&state, game).await;
+}
+
+// Approved players join the next time their page loads, which it does on its own while waiting
+async fn decide_join_request(
+    state: &AppState,
+    game_code: &str,
+    game_join_request_id: u64,
+    user: &User,
+    status: &str,
+) -> Result<Response> {
+    let game = utils::find_host_access_game(state, game_code, user).await?;
+
+    sqlx::query(
+        "UPDATE game_join_requests SET status = $1, decided_by = $2 WHERE game_join_request_id = $3 AND game_code = $4",
+    )
+    .bind(status)
+    .bind(&user.user_id)
+    .bind(&(game_join_request_id as i64))
+    .bind(&game.game_code)
+    .execute(&state.db)
+    .await?;
+
+    return render_join_requests(state, game).await;
+}
+
+async fn game_x_approve_join_request(
+    Path((game_code, game_join_request_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    return decide_join_request(
+        &state,
+        &game_code,
+        game_join_request_id,
+        &user,
+        JOIN_REQUEST_APPROVED,
+    )
+    .await;
+}
+
+async fn game_x_deny_join_request(
+    Path((game_code, game_join_request_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    return decide_join_request(
+        &state,
+        &game_code,
+        game_join_request_id,
+        &user,
+        JOIN_REQUEST_DENIED,
+    )
+    .await;
+}
+
+async fn game_x_redirect(Path(game_code): Path<String>) -> impl IntoResponse {
+    return Redirect::to(&format!("/games/{game_code}"));
+}
+
+async fn game_x_board(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    if can_host(&state, &game, &user).await? {
+        let (drops_count, lead_points) = {
+            let jh1 = {
+                let game_code = game_code.clone();
+                let db = state.db.clone();
+
+                tokio::spawn(async move {
+                    return Ok(sqlx::query_scalar(DROPS_COUNT_QUERY)
+                        .bind(&game_code)
+                        .fetch_optional(&db)
+                        .await?
+                        .unwrap_or(0i64)) as Result<i64>;
+                })
+            };
+
+            let jh2 = {
+                let game_code = game_code.clone();
+                let db = state.db.clone();
+
+                tokio::spawn(async move {
+                    let lead_points: Option<Option<i32>> = sqlx::query_scalar(
+                        "SELECT MAX(points) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+                    )
+                    .bind(&game_code)
+                    .fetch_optional(&db)
+                    .await?;
+                    return Ok(lead_points.flatten().unwrap_or(0)) as Result<i32>;
+                })
+            };
+
+            let (r1, r2) = tokio::join! { jh1, jh2 };
+
+            (r1??, r2??)
+        };
+
+        let (items, players_count, leaders) = {
+            let jh1 = {
+                let game_code = game_code.clone();
+                let db = state.db.clone();
+
+                tokio::spawn(async move {
+                    let items = sqlx::query_as(
+                        r#"
+SELECT *
+FROM game_items
+    LEFT OUTER JOIN (
         SELECT item_id, COUNT(*) AS guess_count
         FROM player_guesses
         WHERE
@@ -1059,64 +2934,903 @@ FROM game_items
         GROUP BY item_id
     ) AS guess_counts ON guess_counts.item_id = game_items.game_item_id
 WHERE
-    game_code = $2
-            "#,
+    game_code = $2
+            "#,
+                    )
+                    .bind(&game_code)
+                    .bind(&game_code)
+                    .fetch_all(&db)
+                    .await?;
+
+                    return Ok(items) as Result<Vec<GameItemWithGuessCount>>;
+                })
+            };
+
+            let jh2 = {
+                let game_code = game_code.clone();
+                let db = state.db.clone();
+
+                tokio::spawn(async move {
+                    return Ok(sqlx::query_scalar(
+                        "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+                    )
+                    .bind(&game_code)
+                    .fetch_optional(&db)
+                    .await?
+                    .unwrap_or(0i64)) as Result<i64>;
+                })
+            };
+
+            let jh3 = {
+                let game_code = game_code.clone();
+                let db = state.db.clone();
+
+                tokio::spawn(async move {
+                    let leaders: Vec<String> =
+                    sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2 AND kicked_at IS NULL")
+                        .bind(&game_code)
+                        .bind(&lead_points)
+                        .fetch_all(&db)
+                        .await?;
+                    return Ok(leaders.join(", ")) as Result<String>;
+                })
+            };
+
+            let (r1, r2, r3) = tokio::join! { jh1, jh2, jh3 };
+
+            (r1??, r2??, r3??)
+        };
+
+        return Ok(Html(GameAsHostBoardTemplate {
+            img_base_uri: state.cfg.r2_bucket_public_url.clone(),
+            game,
+            any_guesses: items
+                .iter()
+                .any(|item| item.guess_count.map(|c| c > 0).unwrap_or(false)),
+            items,
+            players_count,
+            drops_count,
+            leaders,
+            lead_points,
+        })
+        .into_response());
+    }
+
+    let game = as_seen_by_players(game);
+
+    let game_player: Option<GamePlayer> =
+        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL LIMIT 1")
+            .bind(&game_code)
+            .bind(&user.user_id)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let Some(game_player) = game_player else {
+        return Err(anyhow::anyhow!("Player not found"))?;
+    };
+
+    let (guesses, items, host, drops_count) = {
+        let jh1 = {
+            let game_code = game_code.clone();
+            let game_player_id = game_player.game_player_id.clone();
+            let db = state.db.clone();
+
+            tokio::spawn(async move {
+                return Ok(sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL")
+                        .bind(&game_code)
+                        .bind(&game_player_id)
+                        .fetch_all(&db)
+                        .await?) as Result<Vec<PlayerGuess>>;
+            })
+        };
+
+        let jh2 = {
+            let game_code = game_code.clone();
+            let db = state.db.clone();
+
+            tokio::spawn(async move {
+                return Ok(
+                    sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1")
+                        .bind(&game_code)
+                        .fetch_all(&db)
+                        .await?,
+                ) as Result<Vec<GameItem>>;
+            })
+        };
+
+        let jh3 = {
+            let user_id = game.user_id.clone();
+            let db = state.db.clone();
+
+            tokio::spawn(async move {
+                let host: User = sqlx::query_as("SELECT * FROM users WHERE user_id = $1")
+                    .bind(&user_id)
+                    .fetch_one(&db)
+                    .await?;
+                return Ok(host) as Result<User>;
+            })
+        };
+
+        let jh4 = {
+            let game_code = game_code.clone();
+            let db = state.db.clone();
+
+            tokio::spawn(async move {
+                return Ok(sqlx::query_scalar(DROPS_COUNT_QUERY)
+                    .bind(&game_code)
+                    .fetch_optional(&db)
+                    .await?
+                    .unwrap_or(0i64)) as Result<i64>;
+            })
+        };
+
+        let (r1, r2, r3, r4) = tokio::join! { jh1, jh2, jh3, jh4 };
+
+        (r1??, r2??, r3??, r4??)
+    };
+
+    return Ok(Html(GameAsPlayerBoardTemplate {
+        game,
+        host,
+        guesses,
+        items,
+        player: game_player,
+        drops_count,
+        img_base_uri: state.cfg.r2_bucket_public_url.clone(),
+    })
+    .into_response());
+}
+
+#[derive(Template, Clone)]
+#[template(path = "game-as-host-board.html")]
+struct GameAsHostBoardTemplate {
+    game: Game,
+    items: Vec<GameItemWithGuessCount>,
+    any_guesses: bool,
+    players_count: i64,
+    drops_count: i64,
+    leaders: String,
+    lead_points: i32,
+    img_base_uri: String,
+}
+
+async fn game_x_lock(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Active)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    if !game.is_locked {
+        let locked_at_ms = now_ms();
+
+        sqlx::query(
+            "UPDATE games SET is_locked = true, lock_at_ms = NULL, locked_at_ms = $1 WHERE game_code = $2",
+        )
+        .bind(&locked_at_ms)
+        .bind(&game_code)
+        .execute(&state.db)
+        .await?;
+        game.is_locked = true;
+        game.lock_at_ms = None;
+        game.locked_at_ms = Some(locked_at_ms);
+
+        update_round_lock(&state.db, &game, Some(&user.user_id)).await?;
+        refresh_roll_seed(&state.db, &mut game).await?;
+    }
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::Lock,
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return host_board(&state, game).await;
+}
+
+const MAX_LOCK_COUNTDOWN_SECS: i64 = 60 * 60;
+
+#[derive(Deserialize)]
+struct UnlockForm {
+    countdown: Option<String>,
+}
+
+async fn game_x_unlock(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<UnlockForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let countdown = match form.countdown.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(txt) => Some(txt.parse::<i64>()?),
+    };
+
+    if let Some(countdown) = countdown {
+        if !(1..=MAX_LOCK_COUNTDOWN_SECS).contains(&countdown) {
+            return Err(anyhow::anyhow!(
+                "Countdown must be between 1 and {MAX_LOCK_COUNTDOWN_SECS} seconds"
+            ))?;
+        }
+    }
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Active)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let lock_at_ms = countdown.map(|countdown| now_ms() + countdown * 1000);
+    let unlocked_at_ms = if game.is_locked {
+        Some(now_ms())
+    } else {
+        game.unlocked_at_ms
+    };
+
+    if game.is_locked || game.lock_at_ms != lock_at_ms {
+        sqlx::query(
+            "UPDATE games SET is_locked = false, lock_at_ms = $1, locked_at_ms = NULL, unlocked_at_ms = $2 WHERE game_code = $3",
+        )
+        .bind(&lock_at_ms)
+        .bind(&unlocked_at_ms)
+        .bind(&game_code)
+        .execute(&state.db)
+        .await?;
+        game.is_locked = false;
+        game.lock_at_ms = lock_at_ms;
+        game.locked_at_ms = None;
+        game.unlocked_at_ms = unlocked_at_ms;
+
+        update_round_lock(&state.db, &game, Some(&user.user_id)).await?;
+        refresh_roll_seed(&state.db, &mut game).await?;
+    }
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::Unlock { lock_at_ms },
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return host_board(&state, game).await;
+}
+
+async fn game_x_pause(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    if !set_game_status(&state, &mut game, GameStatus::Paused).await? {
+        return Err(anyhow::anyhow!("Game is no longer active"))?;
+    }
+
+    let paused_at_ms = now_ms();
+
+    sqlx::query("UPDATE games SET paused_at_ms = $1 WHERE game_code = $2")
+        .bind(&paused_at_ms)
+        .bind(&game_code)
+        .execute(&state.db)
+        .await?;
+    game.paused_at_ms = Some(paused_at_ms);
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::Pause,
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return host_board(&state, game).await;
+}
+
+async fn game_x_resume(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    // The lock state is left as it was, an open game just gets its countdown and
+    // response times pushed back by however long the pause lasted
+    let paused_for = game
+        .paused_at_ms
+        .map(|paused_at_ms| (now_ms() - paused_at_ms).max(0))
+        .unwrap_or(0);
+
+    if !game.is_locked {
+        game.lock_at_ms = game.lock_at_ms.map(|lock_at_ms| lock_at_ms + paused_for);
+        game.unlocked_at_ms = game
+            .unlocked_at_ms
+            .map(|unlocked_at_ms| unlocked_at_ms + paused_for);
+    }
+
+    sqlx::query(
+        "UPDATE games SET lock_at_ms = $1, unlocked_at_ms = $2, paused_at_ms = NULL WHERE game_code = $3 AND status = $4",
+    )
+    .bind(&game.lock_at_ms)
+    .bind(&game.unlocked_at_ms)
+    .bind(&game_code)
+    .bind(GameStatus::Paused)
+    .execute(&state.db)
+    .await?;
+    game.paused_at_ms = None;
+
+    if !set_game_status(&state, &mut game, GameStatus::Active).await? {
+        return Err(anyhow::anyhow!("Game is no longer paused"))?;
+    }
+
+    publish_host_action(
+        &state,
+        HostAction {
+            game_code: game_code.clone(),
+            typ: HostActionType::Resume,
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
+
+    return host_board(&state, game).await;
+}
+
+async fn game_x_choose_item(
+    Path((game_code, game_item_id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let game_item_id = game_item_id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Active)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    choose_items(
+        &state,
+        &mut game,
+        &[game_item_id],
+        None,
+        ROUND_CLOSED_BY_CHOOSE,
+        Some(&user.user_id),
+    )
+    .await?;
+
+    if game.status == GameStatus::Finished {
+        return Ok(Redirect::to(&format!("/games/{}", game.game_code)).into_response());
+    }
+
+    return host_board(&state, game).await;
+}
+
+async fn game_x_choose_items(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(body): Form<Vec<(String, String)>>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Active)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let mut item_ids = vec![];
+    for (key, value) in body {
+        if key == "item_ids" {
+            let item_id: i64 = value.parse()?;
+
+            if !item_ids.contains(&item_id) {
+                item_ids.push(item_id);
+            }
+        }
+    }
+
+    choose_items(
+        &state,
+        &mut game,
+        &item_ids,
+        None,
+        ROUND_CLOSED_BY_CHOOSE,
+        Some(&user.user_id),
+    )
+    .await?;
+
+    if game.status == GameStatus::Finished {
+        return Ok(Redirect::to(&format!("/games/{}", game.game_code)).into_response());
+    }
+
+    return host_board(&state, game).await;
+}
+
+// Reveals the items as one drop: every open guess resolves against the group,
+// and each guess matching any of the items scores a point
+struct CorrectPlayer {
+    game_player_id: i64,
+    username: String,
+    item_names: Vec<String>,
+    first_guess_id: i64,
+    first_received_at: Option<i64>,
+}
+
+async fn choose_items(
+    state: &AppState,
+    game: &mut Game,
+    item_ids: &[i64],
+    roll_seed: Option<i64>,
+    closed_by: &str,
+    actor: Option<&str>,
+) -> Result<Vec<GameItemOutcome>> {
+    if item_ids.is_empty() {
+        return Err(anyhow::anyhow!("No items chosen"))?;
+    }
+
+    // Revealing before the delay has passed would let late guesses land after the outcome is known
+    if let (true, Some(locked_at_ms)) = (game.is_locked, game.locked_at_ms) {
+        let wait_ms = locked_at_ms + stream_delay_ms(game) - now_ms();
+
+        if wait_ms > 0 {
+            return Err(anyhow::anyhow!(
+                "Still taking guesses from the stream delay, try again in {}s",
+                (wait_ms + 999) / 1000
+            ))?;
+        }
+    }
+
+    let game_items: Vec<GameItem> =
+        sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1 AND game_item_id = ANY($2)")
+            .bind(&game.game_code)
+            .bind(item_ids)
+            .fetch_all(&state.db)
+            .await?;
+
+    if game_items.len() != item_ids.len() {
+        return Err(anyhow::anyhow!("Item not found"))?;
+    }
+
+    if game_items.iter().any(|item| !item.enabled) {
+        return Err(anyhow::anyhow!("Item is disabled"))?;
+    }
+
+    // Points, stock and the next round's seed all change together or not at all
+    let mut tx = state.db.begin().await?;
+
+    let group_id: i64 = sqlx::query_scalar(
+        "SELECT nextval(pg_get_serial_sequence('game_item_outcomes', 'outcome_id'))",
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let mut outcomes: Vec<GameItemOutcome> = vec![sqlx::query_as(
+        "INSERT INTO game_item_outcomes (outcome_id, game_code, item_id, group_id, roll_seed) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+    )
+    .bind(&group_id)
+    .bind(&game.game_code)
+    .bind(&item_ids[0])
+    .bind(&group_id)
+    .bind(&roll_seed)
+    .fetch_one(&mut *tx)
+    .await?];
+
+    for item_id in &item_ids[1..] {
+        outcomes.push(
+            sqlx::query_as(
+                "INSERT INTO game_item_outcomes (game_code, item_id, group_id, roll_seed) VALUES ($1, $2, $3, $4) RETURNING *",
+            )
+            .bind(&game.game_code)
+            .bind(item_id)
+            .bind(&group_id)
+            .bind(&roll_seed)
+            .fetch_one(&mut *tx)
+            .await?,
+        );
+    }
+
+    let correct_guesses: Vec<(i64, String, String, i64, Option<i64>)> = sqlx::query_as(
+        "
+SELECT game_players.game_player_id, users.username, game_items.name, player_guesses.player_guess_id, player_guesses.received_at_ms
+FROM player_guesses
+INNER JOIN game_players ON player_guesses.player_id = game_players.game_player_id
+INNER JOIN users ON game_players.user_id = users.user_id
+INNER JOIN game_items ON player_guesses.item_id = game_items.game_item_id
+WHERE
+    player_guesses.game_code = $1 AND
+    player_guesses.item_id = ANY($2) AND
+    player_guesses.outcome_id IS NULL
+ORDER BY game_players.game_player_id, player_guesses.received_at_ms NULLS LAST
+",
+    )
+    .bind(&game.game_code)
+    .bind(item_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if !correct_guesses.is_empty() {
+        let mut correct_players: Vec<CorrectPlayer> = vec![];
+
+        // Guesses come ordered by player, earliest first
+        for (game_player_id, username, item_name, guess_id, received_at_ms) in correct_guesses {
+            match correct_players.last_mut() {
+                Some(player) if player.game_player_id == game_player_id => {
+                    player.item_names.push(item_name)
+                }
+                _ => correct_players.push(CorrectPlayer {
+                    game_player_id,
+                    username,
+                    item_names: vec![item_name],
+                    first_guess_id: guess_id,
+                    first_received_at: received_at_ms,
+                }),
+            }
+        }
+
+        // The speed bonus ranks players by their earliest correct guess
+        correct_players.sort_by_key(|player| player.first_received_at.unwrap_or(i64::MAX));
+
+        for (rank, player) in correct_players.iter().enumerate() {
+            let response_ms = player
+                .first_received_at
+                .zip(game.unlocked_at_ms)
+                .map(|(received_at_ms, unlocked_at_ms)| (received_at_ms - unlocked_at_ms).max(0));
+            let bonus_points = speed_bonus(game, rank, response_ms);
+
+            if bonus_points > 0 {
+                sqlx::query(
+                    "UPDATE player_guesses SET bonus_points = $1 WHERE player_guess_id = $2",
+                )
+                .bind(&bonus_points)
+                .bind(&player.first_guess_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            sqlx::query("UPDATE game_players SET points = points + $1 WHERE game_player_id = $2")
+                .bind(player.item_names.len() as i32 + bonus_points)
+                .bind(&player.game_player_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if let Some(template_message) = &game.reward_message {
+            let messages = correct_players
+                .iter()
+                .map(|player| {
+                    template_message
+                        .clone()
+                        .replace("<USER>", &player.username)
+                        .replace("<ITEM>", &player.item_names.join(", "))
+                })
+                .collect();
+
+            queue_chat_messages(&mut *tx, &game.game_code, messages).await?;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE player_guesses SET outcome_id = $1, response_ms = GREATEST(received_at_ms - $2, 0) WHERE game_code = $3 AND outcome_id IS NULL",
+    )
+    .bind(&group_id)
+    .bind(&game.unlocked_at_ms)
+    .bind(&game.game_code)
+    .execute(&mut *tx)
+    .await?;
+
+    close_round(&mut *tx, &game.game_code, Some(group_id), closed_by, actor).await?;
+
+    // Items without stock repeat forever and stay guessable
+    sqlx::query(
+        "UPDATE game_items SET stock = GREATEST(stock - 1, 0) WHERE game_code = $1 AND game_item_id = ANY($2) AND stock IS NOT NULL",
+    )
+    .bind(&game.game_code)
+    .bind(item_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE game_items SET enabled = false WHERE game_code = $1 AND game_item_id = ANY($2) AND stock = 0",
+    )
+    .bind(&game.game_code)
+    .bind(item_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    let locked_at_ms = if game.is_locked == game.auto_lock {
+        game.locked_at_ms
+    } else {
+        game.auto_lock.then(now_ms)
+    };
+
+    // Without auto-lock the next round opens as soon as the drop is revealed
+    let unlocked_at_ms = if game.auto_lock {
+        game.unlocked_at_ms
+    } else {
+        Some(now_ms())
+    };
+
+    if game.is_locked != game.auto_lock || game.unlocked_at_ms != unlocked_at_ms {
+        sqlx::query(
+            "UPDATE games SET is_locked = $1, lock_at_ms = NULL, locked_at_ms = $2, unlocked_at_ms = $3 WHERE game_code = $4",
+        )
+        .bind(&game.auto_lock)
+        .bind(&locked_at_ms)
+        .bind(&unlocked_at_ms)
+        .bind(&game.game_code)
+        .execute(&mut *tx)
+        .await?;
+        game.is_locked = game.auto_lock;
+        game.lock_at_ms = None;
+        game.locked_at_ms = locked_at_ms;
+        game.unlocked_at_ms = unlocked_at_ms;
+    }
+
+    open_round(&mut *tx, game).await?;
+
+    // Every drop uses up the round's seed, whether it was rolled or chosen
+    refresh_roll_seed(&mut *tx, game).await?;
+
+    tx.commit().await?;
+
+    publish_host_action(
+        state,
+        HostAction {
+            game_code: game.game_code.clone(),
+            typ: HostActionType::Choose {
+                item_ids: item_ids.iter().map(|id| *id as u64).collect(),
+            },
+            actor: actor.map(str::to_string),
+        },
+    )
+    .await?;
+
+    if game.sudden_death {
+        let contender_points: Vec<i32> = sqlx::query_scalar(
+            "SELECT points FROM game_players WHERE game_code = $1 AND sudden_death = true AND kicked_at IS NULL ORDER BY points DESC LIMIT 2",
+        )
+        .bind(&game.game_code)
+        .fetch_all(&state.db)
+        .await?;
+
+        if let [first, second] = contender_points[..] {
+            if first > second {
+                try_finish_game(state, game, FINISHED_REASON_SUDDEN_DEATH, actor).await?;
+            }
+        }
+    }
+
+    return Ok(outcomes);
+}
+
+// Locked rounds of games with a drop table get a fresh secret seed, unlocked rounds have none.
+// Takes any executor so the lock job can run it inside its claim
+pub async fn refresh_roll_seed(db: impl PgExecutor<'_>, game: &mut Game) -> Result {
+    let roll_seed = game.is_locked.then(rand::random::<i64>);
+    let roll_seed_hash = roll_seed.map(roll_seed_hash);
+
+    let res = sqlx::query(
+        "UPDATE games SET roll_seed = $1, roll_seed_hash = $2 WHERE game_code = $3 AND EXISTS (SELECT 1 FROM game_items WHERE game_items.game_code = games.game_code AND game_items.drop_weight IS NOT NULL)",
+    )
+    .bind(&roll_seed)
+    .bind(&roll_seed_hash)
+    .bind(&game.game_code)
+    .execute(db)
+    .await?;
+
+    if res.rows_affected() > 0 {
+        game.roll_seed = roll_seed;
+        game.roll_seed_hash = roll_seed_hash;
+    }
+
+    return Ok(());
+}
+
+async fn open_round(db: impl PgExecutor<'_>, game: &Game) -> Result {
+    let status = if game.is_locked {
+        ROUND_STATUS_LOCKED
+    } else {
+        ROUND_STATUS_OPEN
+    };
+
+    sqlx::query(
+        "INSERT INTO game_rounds (game_code, status, opened_at_ms, locked_at_ms) VALUES ($1, $2, $3, $4)",
     )
-    .bind(&game_code)
-    .bind(&game_code)
-    .fetch_all(&state.db)
+    .bind(&game.game_code)
+    .bind(status)
+    .bind(now_ms())
+    .bind(&game.locked_at_ms)
+    .execute(db)
     .await?;
 
-    let players_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+    return Ok(());
+}
 
-    let drops_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+pub async fn update_round_lock(
+    db: impl PgExecutor<'_>,
+    game: &Game,
+    actor: Option<&str>,
+) -> Result {
+    let status = if game.is_locked {
+        ROUND_STATUS_LOCKED
+    } else {
+        ROUND_STATUS_OPEN
+    };
 
-    let lead_points: Option<Option<i32>> =
-        sqlx::query_scalar("SELECT MAX(points) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?;
-    let lead_points = lead_points.flatten().unwrap_or(0);
+    sqlx::query(
+        "UPDATE game_rounds SET status = $1, locked_at_ms = $2, locked_by = $3 WHERE game_code = $4 AND status != $5",
+    )
+    .bind(status)
+    .bind(&game.locked_at_ms)
+    .bind(actor.filter(|_| game.is_locked))
+    .bind(&game.game_code)
+    .bind(ROUND_STATUS_RESOLVED)
+    .execute(db)
+    .await?;
 
-    let leaders: Vec<String> =
-        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
-            .bind(&game_code)
-            .bind(&lead_points)
-            .fetch_all(&state.db)
-            .await?;
-    let leaders = leaders.join(", ");
+    return Ok(());
+}
 
-    return Ok(Html(GameAsHostBoardTemplate {
-        img_base_uri: state.cfg.r2_bucket_public_url.clone(),
-        game,
-        any_guesses: items
-            .iter()
-            .any(|item| item.guess_count.map(|c| c > 0).unwrap_or(false)),
-        items,
-        players_count,
-        drops_count,
-        leaders,
-        lead_points,
-    })
-    .into_response());
+// Totals are taken when the round closes, so they survive clearing the guesses afterwards
+async fn close_round(
+    db: impl PgExecutor<'_>,
+    game_code: &str,
+    outcome_id: Option<i64>,
+    closed_by: &str,
+    resolved_by: Option<&str>,
+) -> Result {
+    sqlx::query(
+        r#"
+UPDATE game_rounds
+SET
+    status = $1,
+    resolved_at_ms = $2,
+    outcome_id = $3,
+    closed_by = $4,
+    resolved_by = $5,
+    guesses_count = (
+        SELECT COUNT(*) FROM player_guesses WHERE player_guesses.round_id = game_rounds.game_round_id
+    ),
+    players_count = (
+        SELECT COUNT(DISTINCT player_id) FROM player_guesses WHERE player_guesses.round_id = game_rounds.game_round_id
+    ),
+    correct_count = (
+        SELECT COUNT(*)
+        FROM player_guesses
+            INNER JOIN game_item_outcomes ON
+                game_item_outcomes.group_id = player_guesses.outcome_id AND
+                game_item_outcomes.item_id = player_guesses.item_id
+        WHERE player_guesses.round_id = game_rounds.game_round_id
+    )
+WHERE
+    game_code = $6 AND
+    status != $1
+        "#,
+    )
+    .bind(ROUND_STATUS_RESOLVED)
+    .bind(now_ms())
+    .bind(&outcome_id)
+    .bind(closed_by)
+    .bind(&resolved_by)
+    .bind(game_code)
+    .execute(db)
+    .await?;
+
+    return Ok(());
 }
 
-async fn game_x_choose_item(
-    Path((game_code, game_item_id)): Path<(String, u64)>,
+async fn game_x_roll(
+    Path(game_code): Path<String>,
     session: Session,
     State(state): State<AppState>,
 ) -> Result<Response> {
-    let game_item_id = game_item_id as i64;
     let session_id = utils::session_id(&session)?;
     let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
@@ -1125,11 +3839,12 @@ async fn game_x_choose_item(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
+    .bind(GameStatus::Active)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1137,141 +3852,109 @@ async fn game_x_choose_item(
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
-    let game_item: Option<GameItem> = sqlx::query_as(
-        "SELECT * FROM game_items WHERE game_code = $1 AND game_item_id = $2 LIMIT 1",
-    )
-    .bind(&game_code)
-    .bind(&game_item_id)
-    .fetch_optional(&state.db)
-    .await?;
-
-    let Some(game_item) = game_item else {
-        return Err(anyhow::anyhow!("Item not found"))?;
+    let (true, Some(roll_seed), Some(roll_seed_hash)) =
+        (game.is_locked, game.roll_seed, game.roll_seed_hash.clone())
+    else {
+        return Err(anyhow::anyhow!("Guesses must be locked before rolling"))?;
     };
 
-    if !game_item.enabled {
-        return Err(anyhow::anyhow!("Item is disabled"))?;
-    }
-
-    sqlx::query("INSERT INTO game_item_outcomes (game_code, item_id) VALUES ($1, $2)")
+    let items: Vec<GameItem> = sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1")
         .bind(&game_code)
-        .bind(&game_item_id)
-        .execute(&state.db)
+        .fetch_all(&state.db)
         .await?;
 
-    let outcome: GameItemOutcome = sqlx::query_as(
-        "SELECT * FROM game_item_outcomes WHERE item_id = $1 ORDER BY outcome_id DESC LIMIT 1",
+    let Some(game_item_id) = roll_drop(roll_seed, &items) else {
+        return Err(anyhow::anyhow!(
+            "No enabled items with a drop weight to roll"
+        ))?;
+    };
+
+    choose_items(
+        &state,
+        &mut game,
+        &[game_item_id],
+        Some(roll_seed),
+        ROUND_CLOSED_BY_ROLL,
+        Some(&user.user_id),
     )
-    .bind(&game_item_id)
-    .fetch_one(&state.db)
     .await?;
 
-    let correct_guesses: Vec<(i64, String, String)> = sqlx::query_as(
-        "
-SELECT game_players.game_player_id, users.username, game_items.name
-FROM player_guesses
-INNER JOIN game_players ON player_guesses.player_id = game_players.game_player_id
-INNER JOIN users ON game_players.user_id = users.user_id
-INNER JOIN game_items ON player_guesses.item_id = game_items.game_item_id
-WHERE
-    player_guesses.game_code = $1 AND
-    player_guesses.item_id = $2 AND
-    player_guesses.outcome_id IS NULL
-",
+    let item_name = items
+        .iter()
+        .find(|item| item.game_item_id == game_item_id)
+        .map(|item| item.name.clone())
+        .unwrap_or_default();
+
+    queue_chat_messages(
+        &state.db,
+        &game_code,
+        vec![format!(
+            "Rolled {item_name} with seed {roll_seed} (SHA-256 {roll_seed_hash})"
+        )],
     )
-    .bind(&game_code)
-    .bind(&game_item_id)
-    .fetch_all(&state.db)
     .await?;
 
-    if !correct_guesses.is_empty() {
-        let correct_guess_ids: HashSet<i64> = correct_guesses.iter().map(|x| x.0).collect();
-
-        let q = format!(
-            "UPDATE game_players SET points = points + 1 WHERE game_player_id IN ({})",
-            correct_guess_ids
-                .iter()
-                .enumerate()
-                .map(|(idx, _)| format!("${}", idx + 1))
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
+    if game.status == GameStatus::Finished {
+        return Ok(Redirect::to(&format!("/games/{}", game.game_code)).into_response());
+    }
 
-        let mut query = sqlx::query(&q);
+    return host_board(&state, game).await;
+}
 
-        for id in correct_guess_ids {
-            query = query.bind(id);
+fn speed_bonus(game: &Game, rank: usize, response_ms: Option<i64>) -> i32 {
+    if game.speed_bonus == SPEED_BONUS_FIRST_N {
+        if (rank as i32) < game.speed_bonus_first_n {
+            return game.speed_bonus_points;
         }
 
-        query.execute(&state.db).await?;
-
-        if let Some(template_message) = &game.reward_message {
-            let mut messages = vec![];
-
-            for (_, username, item_name) in correct_guesses {
-                let msg = template_message
-                    .clone()
-                    .replace("<USER>", &username)
-                    .replace("<ITEM>", &item_name);
-
-                messages.push(msg);
-            }
+        return 0;
+    }
 
-            let values = messages
-                .iter()
-                .enumerate()
-                .map(|(idx, _)| format!("(${}, ${}, NULL, false)", idx + 1, idx + 2))
-                .collect::<Vec<_>>()
-                .join(", ");
+    if game.speed_bonus == SPEED_BONUS_DECAY {
+        let Some(response_ms) = response_ms else {
+            return 0;
+        };
 
-            let q = format!(
-                "INSERT INTO chat_messages (game_code, message, lock_id, sent) VALUES {values}"
-            );
+        let decay_ms = game.speed_bonus_decay_secs as f64 * 1000.0;
+        let left = (1.0 - response_ms as f64 / decay_ms).max(0.0);
 
-            let mut q = sqlx::query(&q);
+        return (game.speed_bonus_points as f64 * left).round() as i32;
+    }
 
-            for msg in messages {
-                q = q.bind(&game.game_code).bind(msg);
-            }
+    return 0;
+}
 
-            q.execute(&state.db).await?;
-        }
+async fn queue_chat_messages(
+    db: impl PgExecutor<'_>,
+    game_code: &str,
+    messages: Vec<String>,
+) -> Result {
+    if messages.is_empty() {
+        return Ok(());
     }
 
-    sqlx::query(
-        "UPDATE player_guesses SET outcome_id = $1 WHERE game_code = $2 AND outcome_id IS NULL",
-    )
-    .bind(&outcome.outcome_id)
-    .bind(&game_code)
-    .execute(&state.db)
-    .await?;
+    let values = messages
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| format!("(${}, ${}, NULL, false)", idx * 2 + 1, idx * 2 + 2))
+        .collect::<Vec<_>>()
+        .join(", ");
 
-    sqlx::query("UPDATE game_items SET enabled = false WHERE game_code = $1 AND game_item_id = $2")
-        .bind(&game_code)
-        .bind(&game_item_id)
-        .execute(&state.db)
-        .await?;
+    let q =
+        format!("INSERT INTO chat_messages (game_code, message, lock_id, sent) VALUES {values}");
 
-    if game.is_locked != game.auto_lock {
-        sqlx::query("UPDATE games SET is_locked = $1 WHERE game_code = $2")
-            .bind(&game.auto_lock)
-            .bind(&game_code)
-            .execute(&state.db)
-            .await?;
-        game.is_locked = game.auto_lock;
+    let mut q = sqlx::query(&q);
+
+    for msg in messages {
+        q = q.bind(game_code).bind(msg);
     }
 
-    state
-        .pubsub
-        .host_actions
-        .publish(HostAction {
-            game_code: game_code.clone(),
-            typ: HostActionType::Choose {
-                item_id: game_item_id.clone() as u64,
-            },
-        })
-        .await?;
+    q.execute(db).await?;
 
+    return Ok(());
+}
+
+async fn host_board(state: &AppState, game: Game) -> Result<Response> {
     let items: Vec<GameItemWithGuessCount> = sqlx::query_as(
         r#"
 SELECT *
@@ -1288,35 +3971,36 @@ WHERE
     game_code = $2
             "#,
     )
-    .bind(&game_code)
-    .bind(&game_code)
+    .bind(&game.game_code)
+    .bind(&game.game_code)
     .fetch_all(&state.db)
     .await?;
 
-    let players_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+    let players_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+    )
+    .bind(&game.game_code)
+    .fetch_optional(&state.db)
+    .await?
+    .unwrap_or(0);
 
-    let drops_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+    let drops_count: i64 = sqlx::query_scalar(DROPS_COUNT_QUERY)
+        .bind(&game.game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
 
-    let lead_points: Option<Option<i32>> =
-        sqlx::query_scalar("SELECT MAX(points) FROM game_players WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?;
+    let lead_points: Option<Option<i32>> = sqlx::query_scalar(
+        "SELECT MAX(points) FROM game_players WHERE game_code = $1 AND kicked_at IS NULL",
+    )
+    .bind(&game.game_code)
+    .fetch_optional(&state.db)
+    .await?;
     let lead_points = lead_points.flatten().unwrap_or(0);
 
     let leaders: Vec<String> =
-        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2")
-            .bind(&game_code)
+        sqlx::query_scalar("SELECT users.username FROM game_players INNER JOIN users ON users.user_id = game_players.user_id WHERE game_code = $1 AND points = $2 AND kicked_at IS NULL")
+            .bind(&game.game_code)
             .bind(&lead_points)
             .fetch_all(&state.db)
             .await?;
@@ -1342,13 +4026,23 @@ WHERE
 struct GameAsPlayerBoardTemplate {
     game: Game,
     host: User,
-    guess: Option<PlayerGuess>,
+    guesses: Vec<PlayerGuess>,
     items: Vec<GameItem>,
     player: GamePlayer,
     drops_count: i64,
     img_base_uri: String,
 }
 
+impl GameAsPlayerBoardTemplate {
+    fn is_guessed(&self, item_id: &i64) -> bool {
+        return self.guesses.iter().any(|guess| &guess.item_id == item_id);
+    }
+
+    fn guesses_left(&self) -> i32 {
+        return self.game.max_guesses - self.guesses.len() as i32;
+    }
+}
+
 async fn game_x_guess_item(
     Path((game_code, game_item_id)): Path<(String, u64)>,
     session: Session,
@@ -1358,16 +4052,21 @@ async fn game_x_guess_item(
     let session_id = utils::session_id(&session)?;
     let (user, _) = utils::require_user(&state, &session_id).await?.split();
 
+    if user.is_guest() && !state.guest_guesses.check(&user.user_id) {
+        return Err(anyhow::anyhow!("Too many guesses, slow down"))?;
+    }
+
     if game_code.trim().is_empty() {
         return Err(anyhow::anyhow!("Missing game_code"))?;
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id != $2 AND status = 'ACTIVE' LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND NOT {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
+    .bind(GameStatus::Active)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1375,6 +4074,14 @@ async fn game_x_guess_item(
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
+    let received_at_ms = now_ms();
+
+    if !accepts_guess_at(&game, received_at_ms) {
+        return Err(anyhow::anyhow!("Guesses are locked"))?;
+    }
+
+    let game = as_seen_by_players(game);
+
     let game_item: Option<GameItem> = sqlx::query_as(
         "SELECT * FROM game_items WHERE game_code = $1 AND game_item_id = $2 LIMIT 1",
     )
@@ -1387,31 +4094,85 @@ async fn game_x_guess_item(
         return Err(anyhow::anyhow!("Item not found"))?;
     };
 
+    // Locking the player row makes guesses from two tabs, or chat and web, take turns so
+    // they can't both pass the max_guesses check
+    let mut tx = state.db.begin().await?;
+
     let game_player: Option<GamePlayer> =
-        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 LIMIT 1")
+        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL LIMIT 1 FOR UPDATE")
             .bind(&game_code)
             .bind(&user.user_id)
-            .fetch_optional(&state.db)
+            .fetch_optional(&mut *tx)
             .await?;
 
     let Some(game_player) = game_player else {
         return Err(anyhow::anyhow!("Player not found"))?;
     };
 
-    let guess: Option<PlayerGuess> = sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL LIMIT 1")
-        .bind(&game_code)
-        .bind(&game_player.game_player_id)
-        .fetch_optional(&state.db)
-        .await?;
+    if game.sudden_death && !game_player.sudden_death {
+        return Err(anyhow::anyhow!(
+            "Only the tied players can guess during sudden death"
+        ))?;
+    }
 
-    let guess = if let Some(mut guess) = guess {
-        sqlx::query("UPDATE player_guesses SET item_id = $1 WHERE game_code = $2 AND player_id = $3 AND outcome_id IS NULL LIMIT 1")
-            .bind(&game_item.game_item_id)
-            .bind(&game_code)
-            .bind(&game_player.game_player_id)
-            .execute(&state.db)
+    let guesses: Vec<PlayerGuess> = sqlx::query_as(
+        "SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL",
+    )
+    .bind(&game_code)
+    .bind(&game_player.game_player_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if let Some(guess) = guesses
+        .iter()
+        .find(|guess| guess.item_id == game_item.game_item_id)
+    {
+        // Guessing an already picked item takes the pick back
+        sqlx::query("DELETE FROM player_guesses WHERE player_guess_id = $1")
+            .bind(&guess.player_guess_id)
+            .execute(&mut *tx)
             .await?;
 
+        count_guess_change(&mut *tx, &game_player).await?;
+        tx.commit().await?;
+
+        let new_guess_count = sqlx::query_scalar("SELECT COUNT(*) FROM player_guesses WHERE game_code = $1 AND item_id = $2 AND outcome_id IS NULL")
+            .bind(&game_code)
+            .bind(&game_item.game_item_id)
+            .fetch_optional(&state.db)
+            .await?
+            .unwrap_or(0);
+
+        publish_player_action(
+            &state,
+            PlayerAction {
+                game_code: game_code.clone(),
+                user_id: user.user_id.clone(),
+                typ: PlayerActionType::UndoGuess {
+                    item_id: game_item.game_item_id.clone() as u64,
+                    new_guess_count,
+                },
+            },
+        )
+        .await?;
+    } else if guesses.len() as i32 >= game.max_guesses {
+        // Only single-pick games swap the pick, otherwise a pick must be taken back first
+        let Some(guess) = guesses.first().filter(|_| game.max_guesses == 1) else {
+            return Err(anyhow::anyhow!("No guesses left this round"))?;
+        };
+
+        sqlx::query(
+            "UPDATE player_guesses SET item_id = $1, received_at_ms = $2 WHERE player_guess_id = $3",
+        )
+        .bind(&game_item.game_item_id)
+        .bind(&received_at_ms)
+        .bind(&guess.player_guess_id)
+        .execute(&mut *tx)
+        .await?;
+
+        count_guess_change(&mut *tx, &game_player).await?;
+        tx.commit().await?;
+
         let from_new_guess_count = sqlx::query_scalar("SELECT COUNT(*) FROM player_guesses WHERE game_code = $1 AND item_id = $2 AND outcome_id IS NULL")
             .bind(&game_code)
             .bind(&guess.item_id)
@@ -1426,43 +4187,42 @@ async fn game_x_guess_item(
             .await?
             .unwrap_or(0);
 
-        state
-            .pubsub
-            .player_actions
-            .publish(PlayerAction {
+        publish_player_action(
+            &state,
+            PlayerAction {
                 game_code: game_code.clone(),
                 user_id: user.user_id.clone(),
                 typ: PlayerActionType::UndoGuess {
                     item_id: guess.item_id.clone() as u64,
                     new_guess_count: from_new_guess_count,
                 },
-            })
-            .await?;
+            },
+        )
+        .await?;
 
-        state
-            .pubsub
-            .player_actions
-            .publish(PlayerAction {
+        publish_player_action(
+            &state,
+            PlayerAction {
                 game_code: game_code.clone(),
                 user_id: user.user_id.clone(),
                 typ: PlayerActionType::Guess {
                     item_id: game_item.game_item_id.clone() as u64,
                     new_guess_count: to_new_guess_count,
                 },
-            })
-            .await?;
-
-        guess.item_id = game_item.game_item_id;
-
-        guess
+            },
+        )
+        .await?;
     } else {
-        sqlx::query("INSERT INTO player_guesses (game_code, player_id, item_id, outcome_id) VALUES ($1, $2, $3, $4)")
+        sqlx::query("INSERT INTO player_guesses (game_code, player_id, item_id, outcome_id, received_at_ms, round_id) VALUES ($1, $2, $3, $4, $5, (SELECT game_round_id FROM game_rounds WHERE game_code = $1 AND status != $6 ORDER BY game_round_id DESC LIMIT 1))")
             .bind(&game_code)
             .bind(&game_player.game_player_id)
             .bind(&game_item.game_item_id)
             .bind(None as Option<i64>)
-            .execute(&state.db)
+            .bind(&received_at_ms)
+            .bind(ROUND_STATUS_RESOLVED)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
 
         let new_guess_count = sqlx::query_scalar("SELECT COUNT(*) FROM player_guesses WHERE game_code = $1 AND item_id = $2 AND outcome_id IS NULL")
             .bind(&game_code)
@@ -1471,40 +4231,39 @@ async fn game_x_guess_item(
             .await?
             .unwrap_or(1);
 
-        state
-            .pubsub
-            .player_actions
-            .publish(PlayerAction {
+        publish_player_action(
+            &state,
+            PlayerAction {
                 game_code: game_code.clone(),
                 user_id: user.user_id.clone(),
                 typ: PlayerActionType::Guess {
                     item_id: game_item.game_item_id.clone() as u64,
                     new_guess_count,
                 },
-            })
-            .await?;
+            },
+        )
+        .await?;
 
         if new_guess_count == 1 {
-            state
-                .pubsub
-                .player_actions
-                .publish(PlayerAction {
+            publish_player_action(
+                &state,
+                PlayerAction {
                     game_code: game_code.clone(),
                     user_id: user.user_id.clone(),
                     typ: PlayerActionType::EnableClearGuesses,
-                })
-                .await?;
-        }
-
-        let guess: PlayerGuess = sqlx::query_as("SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND item_id = $3 AND outcome_id IS NULL LIMIT 1")
-            .bind(&game_code)
-            .bind(&game_player.game_player_id)
-            .bind(&game_item.game_item_id)
-            .fetch_one(&state.db)
+                },
+            )
             .await?;
+        }
+    }
 
-        guess
-    };
+    let guesses: Vec<PlayerGuess> = sqlx::query_as(
+        "SELECT * FROM player_guesses WHERE game_code = $1 AND player_id = $2 AND outcome_id IS NULL",
+    )
+    .bind(&game_code)
+    .bind(&game_player.game_player_id)
+    .fetch_all(&state.db)
+    .await?;
 
     let items = sqlx::query_as("SELECT * FROM game_items WHERE game_code = $1")
         .bind(&game_code)
@@ -1516,17 +4275,16 @@ async fn game_x_guess_item(
         .fetch_one(&state.db)
         .await?;
 
-    let drops_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
-            .bind(&game_code)
-            .fetch_optional(&state.db)
-            .await?
-            .unwrap_or(0);
+    let drops_count: i64 = sqlx::query_scalar(DROPS_COUNT_QUERY)
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
 
     return Ok(Html(GameAsPlayerBoardTemplate {
         game,
         host,
-        guess: Some(guess),
+        guesses,
         items,
         player: game_player,
         drops_count,
@@ -1535,6 +4293,17 @@ async fn game_x_guess_item(
     .into_response());
 }
 
+async fn count_guess_change(db: impl PgExecutor<'_>, game_player: &GamePlayer) -> Result {
+    sqlx::query(
+        "UPDATE game_players SET guess_changes = guess_changes + 1 WHERE game_player_id = $1",
+    )
+    .bind(&game_player.game_player_id)
+    .execute(db)
+    .await?;
+
+    return Ok(());
+}
+
 async fn game_x_clear_guesses(
     Path(game_code): Path<String>,
     session: Session,
@@ -1548,31 +4317,45 @@ async fn game_x_clear_guesses(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
+    .bind(GameStatus::Active)
     .fetch_optional(&state.db)
     .await?;
 
-    let Some(_game) = game else {
+    let Some(game) = game else {
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
+    // Clearing throws the round away and starts a fresh one in the same lock state
+    close_round(
+        &state.db,
+        &game_code,
+        None,
+        ROUND_CLOSED_BY_CLEAR,
+        Some(&user.user_id),
+    )
+    .await?;
+
     sqlx::query("DELETE FROM player_guesses WHERE game_code = $1 AND outcome_id IS NULL")
         .bind(&game_code)
         .execute(&state.db)
         .await?;
 
-    state
-        .pubsub
-        .host_actions
-        .publish(HostAction {
+    open_round(&state.db, &game).await?;
+
+    publish_host_action(
+        &state,
+        HostAction {
             game_code: game_code.clone(),
             typ: HostActionType::ClearGuesses,
-        })
-        .await?;
+            actor: Some(user.user_id.clone()),
+        },
+    )
+    .await?;
 
     return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
 }
@@ -1599,11 +4382,12 @@ async fn game_x_enable_item(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
+    .bind(GameStatus::Active)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1652,16 +4436,17 @@ LIMIT 1
 
         game_item.enabled = true;
 
-        state
-            .pubsub
-            .host_actions
-            .publish(HostAction {
+        publish_host_action(
+            &state,
+            HostAction {
                 game_code: game_code.clone(),
                 typ: HostActionType::Enable {
                     item_id: game_item_id.clone() as u64,
                 },
-            })
-            .await?;
+                actor: Some(user.user_id.clone()),
+            },
+        )
+        .await?;
     }
 
     return Ok(Html(GameAsHostItemTemplate {
@@ -1686,11 +4471,12 @@ async fn game_x_disable_item(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = 'ACTIVE' LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
+    .bind(GameStatus::Active)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1739,16 +4525,17 @@ LIMIT 1
 
         game_item.enabled = false;
 
-        state
-            .pubsub
-            .host_actions
-            .publish(HostAction {
+        publish_host_action(
+            &state,
+            HostAction {
                 game_code: game_code.clone(),
                 typ: HostActionType::Disable {
                     item_id: game_item_id.clone() as u64,
                 },
-            })
-            .await?;
+                actor: Some(user.user_id.clone()),
+            },
+        )
+        .await?;
     }
 
     return Ok(Html(GameAsHostItemTemplate {
@@ -1759,7 +4546,7 @@ LIMIT 1
     .into_response());
 }
 
-async fn finish_game(
+async fn start_game_now(
     Path(game_code): Path<String>,
     session: Session,
     State(state): State<AppState>,
@@ -1772,12 +4559,12 @@ async fn finish_game(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = $3 LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
-    .bind(GAME_STATUS_ACTIVE)
+    .bind(GameStatus::Lobby)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1785,107 +4572,489 @@ async fn finish_game(
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
-    if game.status != GAME_STATUS_ACTIVE {
-        return Err(anyhow::anyhow!("Game is not active"))?;
+    start_game(&state, &mut game, Some(&user.user_id)).await?;
+
+    return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
+}
+
+// Moves a game out of the lobby and opens its first round, also used for scheduled starts
+pub async fn start_game(state: &AppState, game: &mut Game, actor: Option<&str>) -> Result {
+    if !set_game_status(state, game, GameStatus::Active).await? {
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+    let unlocked_at_ms = (!game.auto_lock).then(now_ms);
+
+    sqlx::query(
+        "UPDATE games SET active_at = $1, start_at_ms = NULL, is_locked = $2, unlocked_at_ms = $3 WHERE game_code = $4",
+    )
+    .bind(&now)
+    .bind(&game.auto_lock)
+    .bind(&unlocked_at_ms)
+    .bind(&game.game_code)
+    .execute(&state.db)
+    .await?;
+    game.active_at = now;
+    game.start_at_ms = None;
+    game.is_locked = game.auto_lock;
+    game.unlocked_at_ms = unlocked_at_ms;
+
+    open_round(&state.db, game).await?;
+    refresh_roll_seed(&state.db, game).await?;
+
+    publish_host_action(
+        state,
+        HostAction {
+            game_code: game.game_code.clone(),
+            typ: HostActionType::Start,
+            actor: actor.map(str::to_string),
+        },
+    )
+    .await?;
+
+    return Ok(());
+}
+
+// Returns false when another request already moved the game on
+async fn set_game_status(state: &AppState, game: &mut Game, next: GameStatus) -> Result<bool> {
+    if !game.status.can_become(next) {
+        return Err(anyhow::anyhow!(
+            "Game can't go from {} to {next}",
+            game.status
+        ))?;
     }
 
-    sqlx::query("UPDATE games SET status = $1 WHERE game_code = $2")
-        .bind(GAME_STATUS_FINISHED)
+    let res = sqlx::query("UPDATE games SET status = $1 WHERE game_code = $2 AND status = $3")
+        .bind(next)
         .bind(&game.game_code)
+        .bind(game.status)
         .execute(&state.db)
         .await?;
-    game.status = GAME_STATUS_FINISHED.to_string();
 
-    let winners: Vec<(i64, i32, String)> = sqlx::query_as(
+    if res.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    game.status = next;
+
+    return Ok(true);
+}
+
+async fn finish_game(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status != $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Finished)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    try_finish_game(&state, &mut game, FINISHED_REASON_HOST, Some(&user.user_id)).await?;
+
+    return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
+}
+
+// Finishes the game unless the tie-break sends the tied players into sudden death,
+// callers can tell by the game's status afterwards
+pub async fn try_finish_game(
+    state: &AppState,
+    game: &mut Game,
+    finished_reason: &str,
+    actor: Option<&str>,
+) -> Result {
+    let game_code = game.game_code.clone();
+
+    // game_player_id, points, username, guess changes, total time to correct guesses
+    let mut leaders: Vec<(i64, i32, String, i32, Option<i64>)> = sqlx::query_as(&format!(
         r#"
-SELECT game_players.game_player_id, game_players.points, users.username
+SELECT
+    game_players.game_player_id,
+    game_players.points,
+    users.username,
+    game_players.guess_changes,
+    {TOTAL_RESPONSE_MS} AS total_response_ms
 FROM game_players
     INNER JOIN users ON users.user_id = game_players.user_id
 WHERE
     game_players.game_code = $1 AND
-    points != 0 AND
-    points = (
-        SELECT MAX(points)
-        FROM game_players
-        WHERE game_players.game_code = $2
-    )
+    game_players.kicked_at IS NULL AND
+    game_players.points != 0 AND
+    (game_players.sudden_death = true OR $2 = false)
+ORDER BY game_players.points DESC
 "#,
-    )
-    .bind(&game_code)
+    ))
     .bind(&game_code)
+    .bind(&game.sudden_death)
     .fetch_all(&state.db)
     .await?;
 
-    if !winners.is_empty() {
-        let values = winners
+    let lead_points = leaders.first().map(|(_, points, _, _, _)| *points);
+    leaders.retain(|(_, points, _, _, _)| Some(*points) == lead_points);
+
+    if leaders.len() > 1 {
+        if game.tie_break == TIE_BREAK_TIME {
+            let best = leaders
+                .iter()
+                .map(|(_, _, _, _, total_response_ms)| total_response_ms.unwrap_or(i64::MAX))
+                .min();
+
+            leaders.retain(|(_, _, _, _, total_response_ms)| {
+                Some(total_response_ms.unwrap_or(i64::MAX)) == best
+            });
+        } else if game.tie_break == TIE_BREAK_CHANGES {
+            let best = leaders
+                .iter()
+                .map(|(_, _, _, guess_changes, _)| *guess_changes)
+                .min();
+
+            leaders.retain(|(_, _, _, guess_changes, _)| Some(*guess_changes) == best);
+        } else if game.tie_break == TIE_BREAK_RANDOM {
+            let idx = rand::thread_rng().gen_range(0..leaders.len());
+
+            leaders = vec![leaders.swap_remove(idx)];
+        } else if game.tie_break == TIE_BREAK_SUDDEN_DEATH && !game.sudden_death {
+            // Ending the game again during sudden death settles on everyone still tied
+            let game_player_ids: Vec<i64> = leaders
+                .iter()
+                .map(|(game_player_id, _, _, _, _)| *game_player_id)
+                .collect();
+
+            sqlx::query(
+                "UPDATE game_players SET sudden_death = true WHERE game_code = $1 AND game_player_id = ANY($2)",
+            )
+            .bind(&game_code)
+            .bind(&game_player_ids)
+            .execute(&state.db)
+            .await?;
+
+            sqlx::query("UPDATE games SET sudden_death = true WHERE game_code = $1")
+                .bind(&game_code)
+                .execute(&state.db)
+                .await?;
+            game.sudden_death = true;
+
+            publish_host_action(
+                state,
+                HostAction {
+                    game_code: game_code.clone(),
+                    typ: HostActionType::SuddenDeath,
+                    actor: actor.map(str::to_string),
+                },
+            )
+            .await?;
+
+            return Ok(());
+        }
+    }
+
+    close_round(&state.db, &game_code, None, ROUND_CLOSED_BY_FINISH, actor).await?;
+
+    if !set_game_status(state, game, GameStatus::Finished).await? {
+        return Ok(());
+    }
+
+    // Hosts only need telling about games they didn't finish themselves
+    let finished_notice_seen = finished_reason != FINISHED_REASON_ABANDONED;
+    let send_chat = finished_notice_seen || !state.cfg.abandoned_game_suppress_chat;
+
+    sqlx::query(
+        "UPDATE games SET finished_reason = $1, finished_notice_seen = $2, finished_by = $3 WHERE game_code = $4",
+    )
+    .bind(finished_reason)
+    .bind(&finished_notice_seen)
+    .bind(&actor)
+    .bind(&game_code)
+    .execute(&state.db)
+    .await?;
+    game.finished_reason = Some(finished_reason.to_string());
+    game.finished_notice_seen = finished_notice_seen;
+    game.finished_by = actor.map(str::to_string);
+
+    if !leaders.is_empty() {
+        let values = leaders
             .iter()
             .enumerate()
-            .map(|(idx, _)| format!("(${}, ${})", idx + 1, idx + 2))
+            .map(|(idx, _)| format!("(${}, ${})", idx * 2 + 1, idx * 2 + 2))
             .collect::<Vec<_>>()
             .join(", ");
 
         let q = format!("INSERT INTO game_winners (game_player_id, game_code) VALUES {values}");
         let mut query = sqlx::query(&q);
 
-        for (game_player_id, _, _) in &winners {
+        for (game_player_id, _, _, _, _) in &leaders {
             query = query.bind(game_player_id).bind(&game_code);
         }
 
         query.execute(&state.db).await?;
 
-        if let Some(template_message) = &game.total_reward_message {
-            let total: i64 =
-                sqlx::query_scalar("SELECT COUNT(*) FROM game_item_outcomes WHERE game_code = $1")
-                    .bind(&game_code)
-                    .fetch_optional(&state.db)
-                    .await?
-                    .unwrap_or(0);
-
-            let mut messages = vec![];
+        if let (true, Some(template_message)) = (send_chat, &game.total_reward_message) {
+            let total: i64 = sqlx::query_scalar(DROPS_COUNT_QUERY)
+                .bind(&game_code)
+                .fetch_optional(&state.db)
+                .await?
+                .unwrap_or(0);
 
-            for (_, points, username) in winners {
-                let msg = template_message
-                    .clone()
-                    .replace("<USER>", &username)
-                    .replace("<POINTS>", &points.to_string())
-                    .replace("<TOTAL>", &total.to_string());
+            let game_player_ids: Vec<i64> = leaders
+                .iter()
+                .map(|(game_player_id, _, _, _, _)| *game_player_id)
+                .collect();
 
-                messages.push(msg);
-            }
+            let correct_guesses: HashMap<i64, i64> = sqlx::query_as(&format!(
+                "SELECT game_player_id, {CORRECT_GUESSES} FROM game_players WHERE game_player_id = ANY($1)"
+            ))
+            .bind(&game_player_ids)
+            .fetch_all(&state.db)
+            .await?
+            .into_iter()
+            .collect();
 
-            let values = messages
+            let messages = leaders
                 .iter()
-                .enumerate()
-                .map(|(idx, _)| format!("(${}, ${}, NULL, false)", idx + 1, idx + 2))
-                .collect::<Vec<_>>()
-                .join(", ");
+                .map(|(game_player_id, points, username, _, _)| {
+                    let correct = correct_guesses.get(game_player_id).copied().unwrap_or(0);
+
+                    template_message
+                        .clone()
+                        .replace("<USER>", username)
+                        .replace("<POINTS>", &points.to_string())
+                        .replace("<CORRECT>", &correct.to_string())
+                        .replace("<TOTAL>", &total.to_string())
+                })
+                .collect();
+
+            queue_chat_messages(&state.db, &game_code, messages).await?;
+        }
+    }
 
-            let q = format!(
-                "INSERT INTO chat_messages (game_code, message, lock_id, sent) VALUES {values}"
-            );
+    publish_host_action(
+        state,
+        HostAction {
+            game_code,
+            typ: HostActionType::Finish,
+            actor: actor.map(str::to_string),
+        },
+    )
+    .await?;
 
-            let mut q = sqlx::query(&q);
+    return Ok(());
+}
 
-            for msg in messages {
-                q = q.bind(&game.game_code).bind(msg);
-            }
+const MAX_GIVEAWAY_DRAW_COUNT: i32 = 100;
 
-            q.execute(&state.db).await?;
-        }
+#[derive(Deserialize)]
+struct GiveawayForm {
+    pool: String,
+    count: String,
+    include_guests: Option<String>,
+}
+
+async fn post_giveaway(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<GiveawayForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
     }
+    let game_code = game_code.to_lowercase();
 
-    state
-        .pubsub
-        .host_actions
-        .publish(HostAction {
-            game_code: game_code.clone(),
-            typ: HostActionType::Finish,
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status = $3 LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .bind(GameStatus::Finished)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let draw_count: i32 = form.count.trim().parse()?;
+    if !(1..=MAX_GIVEAWAY_DRAW_COUNT).contains(&draw_count) {
+        return Err(anyhow::anyhow!(
+            "Must draw between 1 and {MAX_GIVEAWAY_DRAW_COUNT} names"
+        ))?;
+    }
+
+    // Guests are anyone with a nickname, so they only go in the pool when the host asks
+    let include_guests = form.include_guests.is_some();
+    let guest_pattern = format!("{GUEST_USER_PREFIX}%");
+
+    // Drawn over game_player_ids, sorted so the snapshot is the exact order the seed is applied to
+    let entrants: Vec<(i64, String, String)> = if form.pool == GIVEAWAY_POOL_WINNERS {
+        sqlx::query_as(
+            r#"
+SELECT game_players.game_player_id, users.user_id, users.username
+FROM game_winners
+    INNER JOIN game_players ON game_players.game_player_id = game_winners.game_player_id
+    INNER JOIN users ON users.user_id = game_players.user_id
+WHERE
+    game_winners.game_code = $1 AND
+    ($2 OR users.user_id NOT LIKE $3)
+ORDER BY game_players.game_player_id ASC
+            "#,
+        )
+        .bind(&game.game_code)
+        .bind(&include_guests)
+        .bind(&guest_pattern)
+        .fetch_all(&state.db)
+        .await?
+    } else if form.pool == GIVEAWAY_POOL_SCORERS {
+        sqlx::query_as(
+            r#"
+SELECT game_players.game_player_id, users.user_id, users.username
+FROM game_players
+    INNER JOIN users ON users.user_id = game_players.user_id
+WHERE
+    game_players.game_code = $1 AND
+    game_players.kicked_at IS NULL AND
+    game_players.points > 0 AND
+    ($2 OR users.user_id NOT LIKE $3)
+ORDER BY game_players.game_player_id ASC
+            "#,
+        )
+        .bind(&game.game_code)
+        .bind(&include_guests)
+        .bind(&guest_pattern)
+        .fetch_all(&state.db)
+        .await?
+    } else {
+        return Err(anyhow::anyhow!("Invalid giveaway pool"))?;
+    };
+
+    if entrants.is_empty() {
+        return Err(anyhow::anyhow!("Nobody to draw from"))?;
+    }
+
+    let draw_count = draw_count.min(entrants.len() as i32);
+    let seed: i64 = rand::random();
+
+    let entrant_ids: Vec<i64> = entrants.iter().map(|entrant| entrant.0).collect();
+    let entrant_guests: Vec<bool> = entrants
+        .iter()
+        .map(|entrant| is_guest_id(&entrant.1))
+        .collect();
+    let entrant_names: Vec<String> = entrants.iter().map(|entrant| entrant.2.clone()).collect();
+
+    let winner_ids = draw_winners(seed, &entrant_ids, draw_count as usize);
+    let winners: Vec<String> = winner_ids
+        .iter()
+        .filter_map(|id| entrants.iter().find(|entrant| entrant.0 == *id))
+        .map(|entrant| entrant.2.clone())
+        .collect();
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    let draw: GiveawayDraw = sqlx::query_as(
+        "INSERT INTO giveaway_draws (game_code, pool, seed, draw_count, entrants, winners, created_at, entrant_ids, entrant_guests, winner_ids, method) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING *",
+    )
+    .bind(&game.game_code)
+    .bind(&form.pool)
+    .bind(&seed)
+    .bind(&draw_count)
+    .bind(&entrant_names)
+    .bind(&winners)
+    .bind(&now)
+    .bind(&entrant_ids)
+    .bind(&entrant_guests)
+    .bind(&winner_ids)
+    .bind(GIVEAWAY_METHOD_SHA256_PICKS)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Guests pick their own names, so chat is told which winners are guests
+    let drawn: Vec<String> = draw
+        .winner_list()
+        .iter()
+        .map(|winner| {
+            if winner.is_guest {
+                return format!("{} (guest)", winner.username);
+            }
+
+            return winner.username.clone();
         })
-        .await?;
+        .collect();
+
+    let message = format!(
+        "Giveaway draw #{}: {} (seed {}, verify at {}/games/{}/giveaways/{})",
+        draw.giveaway_draw_id,
+        drawn.join(", "),
+        draw.seed,
+        state.cfg.server_host_uri,
+        game.game_code,
+        draw.giveaway_draw_id,
+    );
+
+    queue_chat_messages(&state.db, &game.game_code, vec![message]).await?;
 
     return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
 }
 
+#[derive(Template)]
+#[template(path = "giveaway.html")]
+struct GiveawayTemplate {
+    game: Game,
+    draw: GiveawayDraw,
+}
+
+async fn giveaway(
+    Path((game_code, giveaway_draw_id)): Path<(String, i32)>,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as("SELECT * FROM games WHERE game_code = $1 LIMIT 1")
+        .bind(&game_code)
+        .fetch_optional(&state.db)
+        .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    let draw: Option<GiveawayDraw> = sqlx::query_as(
+        "SELECT * FROM giveaway_draws WHERE giveaway_draw_id = $1 AND game_code = $2 LIMIT 1",
+    )
+    .bind(&giveaway_draw_id)
+    .bind(&game_code)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(draw) = draw else {
+        return Err(anyhow::anyhow!("Giveaway draw not found"))?;
+    };
+
+    return Ok(Html(GiveawayTemplate { game, draw }).into_response());
+}
+
 #[derive(Template)]
 #[template(
     source = r#"
@@ -1902,6 +5071,49 @@ struct GuessCountTemplate {
     guess_count: i32,
 }
 
+pub fn now_ms() -> i64 {
+    return SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+}
+
+fn stream_delay_ms(game: &Game) -> i64 {
+    return game.stream_delay as i64 * 1000;
+}
+
+/// Viewers see the stream late, so a guess still counts until the lock has reached them
+fn accepts_guess_at(game: &Game, received_at_ms: i64) -> bool {
+    let locked_at_ms = match (game.is_locked, game.locked_at_ms, game.lock_at_ms) {
+        (true, Some(locked_at_ms), _) => locked_at_ms,
+        (true, None, _) => return false,
+        (false, _, Some(lock_at_ms)) if lock_at_ms <= received_at_ms => lock_at_ms,
+        (false, _, _) => return true,
+    };
+
+    return received_at_ms - stream_delay_ms(game) <= locked_at_ms;
+}
+
+/// Shows a game that is still taking delayed guesses as open, counting down from the lock
+fn as_seen_by_players(mut game: Game) -> Game {
+    if let (true, Some(locked_at_ms)) = (game.is_locked, game.locked_at_ms) {
+        if accepts_guess_at(&game, now_ms()) {
+            game.is_locked = false;
+            game.lock_at_ms = Some(locked_at_ms);
+        }
+    }
+
+    return game;
+}
+
+#[derive(Template, Clone)]
+#[template(path = "game-lock-countdown.html")]
+struct LockCountdownTemplate {
+    lock_at_ms: i64,
+    stream_delay: i32,
+    server_now: i64,
+}
+
 async fn host_sse(
     Path(game_code): Path<String>,
     session: Session,
@@ -1915,12 +5127,12 @@ async fn host_sse(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id = $2 AND status = $3 LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} AND status != $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
-    .bind(GAME_STATUS_ACTIVE)
+    .bind(GameStatus::Finished)
     .fetch_optional(&state.db)
     .await?;
 
@@ -1928,8 +5140,8 @@ async fn host_sse(
         return Err(anyhow::anyhow!("Game not found"))?;
     };
 
-    if game.status != GAME_STATUS_ACTIVE {
-        return Err(anyhow::anyhow!("Game is not active"))?;
+    if game.status == GameStatus::Finished {
+        return Err(anyhow::anyhow!("Game is finished"))?;
     }
 
     let guard = state.game_broadcasts.read().unwrap();
@@ -1971,6 +5183,9 @@ async fn host_sse(
 
             PlayerActionType::Join {
                 new_players_count: player_count,
+            }
+            | PlayerActionType::Leave {
+                new_players_count: player_count,
             } => {
                 return Ok(Event::default()
                     .event("players_count")
@@ -2004,6 +5219,12 @@ async fn host_sse(
                     .event(format!("guesses_{item_id}"))
                     .data(data));
             }
+
+            PlayerActionType::RequestJoin { pending_count } => {
+                return Ok(Event::default()
+                    .event("join_requests")
+                    .data(pending_count.to_string()))
+            }
         }
     });
 
@@ -2029,12 +5250,12 @@ async fn player_sse(
     }
     let game_code = game_code.to_lowercase();
 
-    let game: Option<Game> = sqlx::query_as(
-        "SELECT * FROM games WHERE game_code = $1 AND user_id != $2 AND status = $3 LIMIT 1",
-    )
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND NOT {HOST_ACCESS} AND status != $3 LIMIT 1"
+    ))
     .bind(&game_code)
     .bind(&user.user_id)
-    .bind(GAME_STATUS_ACTIVE)
+    .bind(GameStatus::Finished)
     .fetch_optional(&state.db)
     .await?;
 
@@ -2042,12 +5263,12 @@ async fn player_sse(
         return Ok(Redirect::to("/").into_response());
     };
 
-    if game.status != GAME_STATUS_ACTIVE {
+    if game.status == GameStatus::Finished {
         return Ok(Redirect::to(&format!("/games/{game_code}")).into_response());
     }
 
     let game_player: Option<GamePlayer> =
-        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 LIMIT 1")
+        sqlx::query_as("SELECT * FROM game_players WHERE game_code = $1 AND user_id = $2 AND kicked_at IS NULL LIMIT 1")
             .bind(&game_code)
             .bind(&user.user_id)
             .fetch_optional(&state.db)
@@ -2084,27 +5305,48 @@ async fn player_sse(
 
     let rx = broadcast.to_players.subscribe();
 
-    let stream = BroadcastStream::new(rx).map(|event| -> Result<Event> {
+    let stream = BroadcastStream::new(rx).map(move |event| -> Result<Event> {
         let event = event?;
 
         match &event.typ {
-            HostActionType::Finish => {
+            HostActionType::Start | HostActionType::Finish => {
                 return Ok(Event::default().event(format!("force_refresh")).data(""))
             }
 
+            // The kicked player's page reloads, which either rejoins them or shows the ban
+            HostActionType::Kick { user_id } if user_id == &user.user_id => {
+                return Ok(Event::default().event("force_refresh").data(""))
+            }
+
             // HostActionType::Disable { item_id } => {
             //     return Ok(Event::default()
             //         .event(format!("update_item_{item_id}"))
             //         .data(""))
             // }
+            HostActionType::Unlock {
+                lock_at_ms: Some(lock_at_ms),
+            } => {
+                let data = LockCountdownTemplate {
+                    lock_at_ms: *lock_at_ms,
+                    stream_delay: game.stream_delay,
+                    server_now: now_ms(),
+                }
+                .render()?;
+
+                return Ok(Event::default().event("countdown").data(data));
+            }
+
             HostActionType::Lock
-            | HostActionType::Unlock
+            | HostActionType::Unlock { lock_at_ms: None }
             | HostActionType::ClearGuesses
             | HostActionType::Enable { .. }
             | HostActionType::Disable { .. }
-            | HostActionType::Choose { .. } => {
-                return Ok(Event::default().event("host_action").data(""))
-            }
+            | HostActionType::Choose { .. }
+            | HostActionType::SuddenDeath
+            | HostActionType::Kick { .. }
+            | HostActionType::AdjustPoints { .. }
+            | HostActionType::Pause
+            | HostActionType::Resume => return Ok(Event::default().event("host_action").data("")),
         }
     });
 
@@ -3,7 +3,12 @@ use std::{collections::HashMap, io::BufWriter, sync::Arc};
 use super::utils;
 
 use crate::{
-    models::{GameItemTemplate, GameTemplate, SessionAuth, User},
+    models::{
+        GameItemTemplate, GameTemplate, SessionAuth, User, LATE_JOIN_AFTER_DROPS,
+        LATE_JOIN_ANYTIME, LATE_JOIN_FIRST_LOCK, SPEED_BONUS_DECAY, SPEED_BONUS_FIRST_N,
+        SPEED_BONUS_NONE, TIE_BREAK_CHANGES, TIE_BREAK_NONE, TIE_BREAK_RANDOM,
+        TIE_BREAK_SUDDEN_DEATH, TIE_BREAK_TIME,
+    },
     prelude::*,
 };
 
@@ -23,9 +28,19 @@ use serde::Deserialize;
 use tower_sessions::Session;
 
 const MAX_TEMPLATES_PER_USER: usize = 100;
+const MAX_GUESSES_PER_ROUND: i32 = 10;
+const MAX_STREAM_DELAY_SECS: i32 = 5 * 60;
+const MAX_SPEED_BONUS_POINTS: i32 = 100;
+const MAX_SPEED_BONUS_FIRST_N: i32 = 100;
+const MAX_SPEED_BONUS_DECAY_SECS: i32 = 60 * 60;
+const MAX_PLAYERS: i32 = 100_000;
+const MAX_LATE_JOIN_DROPS: i32 = 100;
 
 const DEFAULT_REWARD_MSG: &str = "<USER> correctly guessed <ITEM>";
-const DEFAULT_TOTAL_REWARD_MSG: &str = "<USER> won with <POINTS>/<TOTAL> correct guesses";
+const DEFAULT_TOTAL_REWARD_MSG: &str = "<USER> won with <CORRECT>/<TOTAL> correct guesses";
+
+// name, image key, start enabled, stock, drop weight
+type ItemRow = (String, Option<String>, bool, Option<i32>, Option<i32>);
 
 pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
     return router
@@ -427,9 +442,31 @@ async fn post_template(
     let sid = utils::session_id(&session)?;
     let (user, _) = utils::require_user(&state, &sid).await?.split();
 
+    // Hosting leans on the host's Twitch account for chat and eligibility checks
+    if user.is_guest() {
+        return Err(anyhow::anyhow!("Guests can't host games"))?;
+    }
+
     let mut name = None;
 
     let mut auto_lock = None;
+    let mut max_guesses = None;
+    let mut stream_delay = None;
+
+    let mut speed_bonus = None;
+    let mut speed_bonus_points = None;
+    let mut speed_bonus_first_n = None;
+    let mut speed_bonus_decay_secs = None;
+    let mut tie_break = None;
+
+    let mut eligible_followers = None;
+    let mut eligible_subscribers = None;
+    let mut eligible_vips = None;
+
+    let mut max_players = None;
+    let mut late_join = None;
+    let mut late_join_drops = None;
+    let mut late_join_catch_up = None;
 
     let mut should_post = None;
     let mut post_msg = None;
@@ -457,6 +494,92 @@ async fn post_template(
                 _ => auto_lock = Some(false),
             },
 
+            Some("max-guesses") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    max_guesses = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("stream-delay") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    stream_delay = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("speed-bonus") => {
+                speed_bonus = Some(field.text().await?.trim().to_string());
+            }
+            Some("speed-bonus-points") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_points = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("speed-bonus-first-n") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_first_n = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("tie-break") => {
+                tie_break = Some(field.text().await?.trim().to_string());
+            }
+            Some("speed-bonus-decay") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_decay_secs = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("eligible-followers") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_followers = Some(true),
+                _ => eligible_followers = Some(false),
+            },
+            Some("eligible-subscribers") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_subscribers = Some(true),
+                _ => eligible_subscribers = Some(false),
+            },
+            Some("eligible-vips") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_vips = Some(true),
+                _ => eligible_vips = Some(false),
+            },
+
+            Some("max-players") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    max_players = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("late-join") => {
+                late_join = Some(field.text().await?.trim().to_string());
+            }
+            Some("late-join-drops") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    late_join_drops = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("late-join-catch-up") => match field.bytes().await?.as_ref() {
+                b"on" => late_join_catch_up = Some(true),
+                _ => late_join_catch_up = Some(false),
+            },
+
             Some("should-post") => match field.bytes().await?.as_ref() {
                 b"on" => should_post = Some(true),
                 _ => should_post = Some(false),
@@ -490,8 +613,8 @@ async fn post_template(
 
                 let idx: usize = item_field_name[6..close_idx].parse()?;
 
-                let (item_name, item_image, start_enabled) =
-                    items.entry(idx).or_insert((None, None, None));
+                let (item_name, item_image, start_enabled, stock, drop_weight) =
+                    items.entry(idx).or_insert((None, None, None, None, None));
 
                 match &item_field_name[(close_idx + 2)..] {
                     "name" => {
@@ -529,6 +652,42 @@ async fn post_template(
                             _ => None,
                         };
                     }
+                    "stock" => {
+                        let txt = field.text().await?;
+                        let txt = txt.trim();
+
+                        // blank means the item can drop any number of times
+                        if !txt.is_empty() {
+                            let value = txt.parse::<i32>()?;
+
+                            if value < 1 {
+                                return Err(anyhow::anyhow!(
+                                    "Item {} stock must be at least 1",
+                                    idx + 1
+                                ))?;
+                            }
+
+                            *stock = Some(value);
+                        }
+                    }
+                    "drop_weight" => {
+                        let txt = field.text().await?;
+                        let txt = txt.trim();
+
+                        // blank leaves the item out of server rolls
+                        if !txt.is_empty() {
+                            let value = txt.parse::<i32>()?;
+
+                            if value < 1 {
+                                return Err(anyhow::anyhow!(
+                                    "Item {} drop weight must be at least 1",
+                                    idx + 1
+                                ))?;
+                            }
+
+                            *drop_weight = Some(value);
+                        }
+                    }
 
                     // ignore
                     _ => {}
@@ -546,6 +705,49 @@ async fn post_template(
 
     let auto_lock = auto_lock.unwrap_or(false);
 
+    let max_guesses = max_guesses.unwrap_or(1);
+    if !(1..=MAX_GUESSES_PER_ROUND).contains(&max_guesses) {
+        return Err(anyhow::anyhow!(
+            "Guesses per round must be between 1 and {MAX_GUESSES_PER_ROUND}"
+        ))?;
+    }
+
+    let stream_delay = stream_delay.unwrap_or(0);
+    if !(0..=MAX_STREAM_DELAY_SECS).contains(&stream_delay) {
+        return Err(anyhow::anyhow!(
+            "Stream delay must be between 0 and {MAX_STREAM_DELAY_SECS} seconds"
+        ))?;
+    }
+
+    let (speed_bonus, speed_bonus_points, speed_bonus_first_n, speed_bonus_decay_secs) =
+        validate_speed_bonus(
+            speed_bonus,
+            speed_bonus_points,
+            speed_bonus_first_n,
+            speed_bonus_decay_secs,
+        )?;
+
+    let tie_break = tie_break.unwrap_or(TIE_BREAK_NONE.to_string());
+    if ![
+        TIE_BREAK_NONE,
+        TIE_BREAK_TIME,
+        TIE_BREAK_CHANGES,
+        TIE_BREAK_RANDOM,
+        TIE_BREAK_SUDDEN_DEATH,
+    ]
+    .contains(&tie_break.as_str())
+    {
+        return Err(anyhow::anyhow!("Unknown tie-break: {tie_break}"))?;
+    }
+
+    let eligible_followers = eligible_followers.unwrap_or(false);
+    let eligible_subscribers = eligible_subscribers.unwrap_or(false);
+    let eligible_vips = eligible_vips.unwrap_or(false);
+
+    let (max_players, late_join, late_join_drops) =
+        validate_late_join(max_players, late_join, late_join_drops)?;
+    let late_join_catch_up = late_join_catch_up.unwrap_or(false);
+
     let reward_message = should_post.map(|_| post_msg.unwrap_or(DEFAULT_REWARD_MSG.to_string()));
     let total_reward_message =
         should_post_total.map(|_| post_total_msg.unwrap_or(DEFAULT_TOTAL_REWARD_MSG.to_string()));
@@ -557,7 +759,7 @@ async fn post_template(
         keys.sort();
 
         for key in keys {
-            let (name, mut img, start_enabled) = items.remove(&key).unwrap();
+            let (name, mut img, start_enabled, stock, drop_weight) = items.remove(&key).unwrap();
 
             let Some(name) = name else {
                 return Err(anyhow::anyhow!("Item {} has no name", key + 1))?;
@@ -587,7 +789,13 @@ async fn post_template(
                 return Ok(img) as Result<Option<(String, Bytes)>>;
             });
 
-            list.push((name, img_jh, start_enabled.unwrap_or(false)));
+            list.push((
+                name,
+                img_jh,
+                start_enabled.unwrap_or(false),
+                stock,
+                drop_weight,
+            ));
         }
 
         list
@@ -596,7 +804,7 @@ async fn post_template(
     let items = {
         let mut list = vec![];
 
-        for (name, img_jh, start_enabled) in items {
+        for (name, img_jh, start_enabled, stock, drop_weight) in items {
             let bucket = state.bucket.clone();
             list.push(tokio::spawn(async move {
                 let img = if let Some((filename, bytes)) = img_jh.await?? {
@@ -609,7 +817,7 @@ async fn post_template(
                     None
                 };
 
-                return Ok((name, img, start_enabled)) as Result<(String, Option<String>, bool)>;
+                return Ok((name, img, start_enabled, stock, drop_weight)) as Result<ItemRow>;
             }));
         }
 
@@ -622,12 +830,26 @@ async fn post_template(
         out
     };
 
-    sqlx::query("INSERT INTO game_templates (user_id, name, auto_lock, reward_message, total_reward_message) VALUES ($1, $2, $3, $4, $5)")
+    sqlx::query("INSERT INTO game_templates (user_id, name, auto_lock, max_guesses, stream_delay, speed_bonus, speed_bonus_points, speed_bonus_first_n, speed_bonus_decay_secs, tie_break, reward_message, total_reward_message, eligible_followers, eligible_subscribers, eligible_vips, max_players, late_join, late_join_drops, late_join_catch_up) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)")
         .bind(&user.user_id)
         .bind(&name)
         .bind(&auto_lock)
+        .bind(&max_guesses)
+        .bind(&stream_delay)
+        .bind(&speed_bonus)
+        .bind(&speed_bonus_points)
+        .bind(&speed_bonus_first_n)
+        .bind(&speed_bonus_decay_secs)
+        .bind(&tie_break)
         .bind(&reward_message)
         .bind(&total_reward_message)
+        .bind(&eligible_followers)
+        .bind(&eligible_subscribers)
+        .bind(&eligible_vips)
+        .bind(&max_players)
+        .bind(&late_join)
+        .bind(&late_join_drops)
+        .bind(&late_join_catch_up)
         .execute(&state.db)
         .await?;
 
@@ -640,23 +862,36 @@ async fn post_template(
 
     if !items.is_empty() {
         let query = format!(
-            "INSERT INTO game_item_templates (game_template_id, name, image, start_enabled) VALUES {}",
+            "INSERT INTO game_item_templates (game_template_id, name, image, start_enabled, stock, drop_weight) VALUES {}",
             items
                 .iter()
                 .enumerate()
-                .map(|(idx, _)| format!("(${}, ${}, ${}, ${})", idx + 1, idx + 2, idx + 3, idx + 4))
+                .map(|(idx, _)| {
+                    let i = idx * 6;
+                    format!(
+                        "(${}, ${}, ${}, ${}, ${}, ${})",
+                        i + 1,
+                        i + 2,
+                        i + 3,
+                        i + 4,
+                        i + 5,
+                        i + 6
+                    )
+                })
                 .collect::<Vec<String>>()
                 .join(",")
         );
 
         let mut q = sqlx::query(&query);
 
-        for (name, img, start_enabled) in items {
+        for (name, img, start_enabled, stock, drop_weight) in items {
             q = q
                 .bind(&record.game_template_id)
                 .bind(name)
                 .bind(img)
-                .bind(start_enabled);
+                .bind(start_enabled)
+                .bind(stock)
+                .bind(drop_weight);
         }
 
         q.execute(&state.db).await?;
@@ -701,6 +936,23 @@ async fn put_template(
     let mut name = None;
 
     let mut auto_lock = None;
+    let mut max_guesses = None;
+    let mut stream_delay = None;
+
+    let mut speed_bonus = None;
+    let mut speed_bonus_points = None;
+    let mut speed_bonus_first_n = None;
+    let mut speed_bonus_decay_secs = None;
+    let mut tie_break = None;
+
+    let mut eligible_followers = None;
+    let mut eligible_subscribers = None;
+    let mut eligible_vips = None;
+
+    let mut max_players = None;
+    let mut late_join = None;
+    let mut late_join_drops = None;
+    let mut late_join_catch_up = None;
 
     let mut should_post = None;
     let mut post_msg = None;
@@ -728,6 +980,92 @@ async fn put_template(
                 _ => auto_lock = Some(false),
             },
 
+            Some("max-guesses") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    max_guesses = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("stream-delay") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    stream_delay = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("speed-bonus") => {
+                speed_bonus = Some(field.text().await?.trim().to_string());
+            }
+            Some("speed-bonus-points") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_points = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("speed-bonus-first-n") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_first_n = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("tie-break") => {
+                tie_break = Some(field.text().await?.trim().to_string());
+            }
+            Some("speed-bonus-decay") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    speed_bonus_decay_secs = Some(txt.parse::<i32>()?);
+                }
+            }
+
+            Some("eligible-followers") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_followers = Some(true),
+                _ => eligible_followers = Some(false),
+            },
+            Some("eligible-subscribers") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_subscribers = Some(true),
+                _ => eligible_subscribers = Some(false),
+            },
+            Some("eligible-vips") => match field.bytes().await?.as_ref() {
+                b"on" => eligible_vips = Some(true),
+                _ => eligible_vips = Some(false),
+            },
+
+            Some("max-players") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    max_players = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("late-join") => {
+                late_join = Some(field.text().await?.trim().to_string());
+            }
+            Some("late-join-drops") => {
+                let txt = field.text().await?;
+                let txt = txt.trim();
+
+                if !txt.is_empty() {
+                    late_join_drops = Some(txt.parse::<i32>()?);
+                }
+            }
+            Some("late-join-catch-up") => match field.bytes().await?.as_ref() {
+                b"on" => late_join_catch_up = Some(true),
+                _ => late_join_catch_up = Some(false),
+            },
+
             Some("should-post") => match field.bytes().await?.as_ref() {
                 b"on" => should_post = Some(true),
                 _ => should_post = Some(false),
@@ -761,8 +1099,9 @@ async fn put_template(
 
                 let idx: usize = item_field_name[6..close_idx].parse()?;
 
-                let (item_id, item_name, item_image, start_enabled) =
-                    items.entry(idx).or_insert((None, None, None, None));
+                let (item_id, item_name, item_image, start_enabled, stock, drop_weight) = items
+                    .entry(idx)
+                    .or_insert((None, None, None, None, None, None));
 
                 match &item_field_name[(close_idx + 2)..] {
                     "id" => {
@@ -803,6 +1142,42 @@ async fn put_template(
                             _ => None,
                         };
                     }
+                    "stock" => {
+                        let txt = field.text().await?;
+                        let txt = txt.trim();
+
+                        // blank means the item can drop any number of times
+                        if !txt.is_empty() {
+                            let value = txt.parse::<i32>()?;
+
+                            if value < 1 {
+                                return Err(anyhow::anyhow!(
+                                    "Item {} stock must be at least 1",
+                                    idx + 1
+                                ))?;
+                            }
+
+                            *stock = Some(value);
+                        }
+                    }
+                    "drop_weight" => {
+                        let txt = field.text().await?;
+                        let txt = txt.trim();
+
+                        // blank leaves the item out of server rolls
+                        if !txt.is_empty() {
+                            let value = txt.parse::<i32>()?;
+
+                            if value < 1 {
+                                return Err(anyhow::anyhow!(
+                                    "Item {} drop weight must be at least 1",
+                                    idx + 1
+                                ))?;
+                            }
+
+                            *drop_weight = Some(value);
+                        }
+                    }
 
                     // ignore
                     _ => {}
@@ -820,6 +1195,49 @@ async fn put_template(
 
     let auto_lock = auto_lock.unwrap_or(false);
 
+    let max_guesses = max_guesses.unwrap_or(1);
+    if !(1..=MAX_GUESSES_PER_ROUND).contains(&max_guesses) {
+        return Err(anyhow::anyhow!(
+            "Guesses per round must be between 1 and {MAX_GUESSES_PER_ROUND}"
+        ))?;
+    }
+
+    let stream_delay = stream_delay.unwrap_or(0);
+    if !(0..=MAX_STREAM_DELAY_SECS).contains(&stream_delay) {
+        return Err(anyhow::anyhow!(
+            "Stream delay must be between 0 and {MAX_STREAM_DELAY_SECS} seconds"
+        ))?;
+    }
+
+    let (speed_bonus, speed_bonus_points, speed_bonus_first_n, speed_bonus_decay_secs) =
+        validate_speed_bonus(
+            speed_bonus,
+            speed_bonus_points,
+            speed_bonus_first_n,
+            speed_bonus_decay_secs,
+        )?;
+
+    let tie_break = tie_break.unwrap_or(TIE_BREAK_NONE.to_string());
+    if ![
+        TIE_BREAK_NONE,
+        TIE_BREAK_TIME,
+        TIE_BREAK_CHANGES,
+        TIE_BREAK_RANDOM,
+        TIE_BREAK_SUDDEN_DEATH,
+    ]
+    .contains(&tie_break.as_str())
+    {
+        return Err(anyhow::anyhow!("Unknown tie-break: {tie_break}"))?;
+    }
+
+    let eligible_followers = eligible_followers.unwrap_or(false);
+    let eligible_subscribers = eligible_subscribers.unwrap_or(false);
+    let eligible_vips = eligible_vips.unwrap_or(false);
+
+    let (max_players, late_join, late_join_drops) =
+        validate_late_join(max_players, late_join, late_join_drops)?;
+    let late_join_catch_up = late_join_catch_up.unwrap_or(false);
+
     let reward_message = should_post.map(|_| post_msg.unwrap_or(DEFAULT_REWARD_MSG.to_string()));
     let total_reward_message =
         should_post_total.map(|_| post_total_msg.unwrap_or(DEFAULT_TOTAL_REWARD_MSG.to_string()));
@@ -831,7 +1249,8 @@ async fn put_template(
         keys.sort();
 
         for key in keys {
-            let (id, name, mut img, start_enabled) = items.remove(&key).unwrap();
+            let (id, name, mut img, start_enabled, stock, drop_weight) =
+                items.remove(&key).unwrap();
 
             let Some(name) = name else {
                 return Err(anyhow::anyhow!("Item {} has no name", key + 1))?;
@@ -869,7 +1288,14 @@ async fn put_template(
                 }
             }
 
-            list.push((id, name, img_jh, start_enabled.unwrap_or(false)));
+            list.push((
+                id,
+                name,
+                img_jh,
+                start_enabled.unwrap_or(false),
+                stock,
+                drop_weight,
+            ));
         }
 
         list
@@ -881,7 +1307,7 @@ async fn put_template(
         let mut to_create = vec![];
         let mut to_update = vec![];
 
-        for (id, name, img_jh, start_enabled) in items {
+        for (id, name, img_jh, start_enabled, stock, drop_weight) in items {
             let is_update = id.is_some();
 
             let bucket = state.bucket.clone();
@@ -907,8 +1333,8 @@ async fn put_template(
                     None
                 };
 
-                return Ok((id, name, img, start_enabled))
-                    as Result<(Option<i64>, String, Option<String>, bool)>;
+                return Ok((id, (name, img, start_enabled, stock, drop_weight)))
+                    as Result<(Option<i64>, ItemRow)>;
             });
 
             if is_update {
@@ -921,11 +1347,25 @@ async fn put_template(
         (to_create, to_update)
     };
 
-    sqlx::query("UPDATE game_templates SET name = $1, auto_lock = $2, reward_message = $3, total_reward_message = $4 WHERE game_template_id = $5 AND user_id = $6")
+    sqlx::query("UPDATE game_templates SET name = $1, auto_lock = $2, max_guesses = $3, stream_delay = $4, speed_bonus = $5, speed_bonus_points = $6, speed_bonus_first_n = $7, speed_bonus_decay_secs = $8, tie_break = $9, reward_message = $10, total_reward_message = $11, eligible_followers = $12, eligible_subscribers = $13, eligible_vips = $14, max_players = $15, late_join = $16, late_join_drops = $17, late_join_catch_up = $18 WHERE game_template_id = $19 AND user_id = $20")
         .bind(&name)
         .bind(&auto_lock)
+        .bind(&max_guesses)
+        .bind(&stream_delay)
+        .bind(&speed_bonus)
+        .bind(&speed_bonus_points)
+        .bind(&speed_bonus_first_n)
+        .bind(&speed_bonus_decay_secs)
+        .bind(&tie_break)
         .bind(&reward_message)
         .bind(&total_reward_message)
+        .bind(&eligible_followers)
+        .bind(&eligible_subscribers)
+        .bind(&eligible_vips)
+        .bind(&max_players)
+        .bind(&late_join)
+        .bind(&late_join_drops)
+        .bind(&late_join_catch_up)
         .bind(&id)
         .bind(&user.user_id)
         .execute(&state.db)
@@ -933,11 +1373,22 @@ async fn put_template(
 
     if !items_to_create.is_empty() {
         let query = format!(
-            "INSERT INTO game_item_templates (game_template_id, name, image, start_enabled) VALUES {}",
+            "INSERT INTO game_item_templates (game_template_id, name, image, start_enabled, stock, drop_weight) VALUES {}",
             items_to_create
                 .iter()
                 .enumerate()
-                .map(|(idx, _)| format!("(${}, ${}, ${}, ${})", idx + 1, idx + 2, idx + 3, idx + 4))
+                .map(|(idx, _)| {
+                    let i = idx * 6;
+                    format!(
+                        "(${}, ${}, ${}, ${}, ${}, ${})",
+                        i + 1,
+                        i + 2,
+                        i + 3,
+                        i + 4,
+                        i + 5,
+                        i + 6
+                    )
+                })
                 .collect::<Vec<String>>()
                 .join(",")
         );
@@ -945,9 +1396,15 @@ async fn put_template(
         let mut q = sqlx::query(&query);
 
         for r in join_all(items_to_create).await {
-            let (_, name, img, start_enabled) = r??;
+            let (_, (name, img, start_enabled, stock, drop_weight)) = r??;
 
-            q = q.bind(&id).bind(name).bind(img).bind(start_enabled);
+            q = q
+                .bind(&id)
+                .bind(name)
+                .bind(img)
+                .bind(start_enabled)
+                .bind(stock)
+                .bind(drop_weight);
         }
 
         q.execute(&state.db).await?;
@@ -959,13 +1416,15 @@ async fn put_template(
         Arc::try_unwrap(prev_game_items).expect("Other instances should be dropped by now");
 
     for r in items_to_update {
-        let (id, name, img, start_enabled) = r??;
+        let (id, (name, img, start_enabled, stock, drop_weight)) = r??;
         let id = id.expect("id must be some value here");
 
-        sqlx::query("UPDATE game_item_templates SET name = $1, image = $2, start_enabled = $3 WHERE game_item_template_id = $4")
+        sqlx::query("UPDATE game_item_templates SET name = $1, image = $2, start_enabled = $3, stock = $4, drop_weight = $5 WHERE game_item_template_id = $6")
                 .bind(&name)
                 .bind(&img)
                 .bind(&start_enabled)
+                .bind(&stock)
+                .bind(&drop_weight)
                 .bind(&id)
                 .execute(&state.db)
                 .await?;
@@ -1028,3 +1487,80 @@ async fn delete_template(
 
     return Ok("".into_response());
 }
+
+fn validate_speed_bonus(
+    mode: Option<String>,
+    points: Option<i32>,
+    first_n: Option<i32>,
+    decay_secs: Option<i32>,
+) -> Result<(String, i32, i32, i32)> {
+    let mode = mode.unwrap_or(SPEED_BONUS_NONE.to_string());
+
+    if mode == SPEED_BONUS_NONE {
+        return Ok((mode, 0, 0, 0));
+    }
+
+    let points = points.unwrap_or(0);
+    if !(1..=MAX_SPEED_BONUS_POINTS).contains(&points) {
+        return Err(anyhow::anyhow!(
+            "Speed bonus must be between 1 and {MAX_SPEED_BONUS_POINTS} points"
+        ))?;
+    }
+
+    if mode == SPEED_BONUS_FIRST_N {
+        let first_n = first_n.unwrap_or(0);
+        if !(1..=MAX_SPEED_BONUS_FIRST_N).contains(&first_n) {
+            return Err(anyhow::anyhow!(
+                "Speed bonus must go to between 1 and {MAX_SPEED_BONUS_FIRST_N} players"
+            ))?;
+        }
+
+        return Ok((mode, points, first_n, 0));
+    }
+
+    if mode == SPEED_BONUS_DECAY {
+        let decay_secs = decay_secs.unwrap_or(0);
+        if !(1..=MAX_SPEED_BONUS_DECAY_SECS).contains(&decay_secs) {
+            return Err(anyhow::anyhow!(
+                "Speed bonus must decay over 1 to {MAX_SPEED_BONUS_DECAY_SECS} seconds"
+            ))?;
+        }
+
+        return Ok((mode, points, 0, decay_secs));
+    }
+
+    return Err(anyhow::anyhow!("Unknown speed bonus: {mode}"))?;
+}
+
+fn validate_late_join(
+    max_players: Option<i32>,
+    late_join: Option<String>,
+    late_join_drops: Option<i32>,
+) -> Result<(Option<i32>, String, i32)> {
+    if let Some(max_players) = max_players {
+        if !(1..=MAX_PLAYERS).contains(&max_players) {
+            return Err(anyhow::anyhow!(
+                "Max players must be between 1 and {MAX_PLAYERS}"
+            ))?;
+        }
+    }
+
+    let late_join = late_join.unwrap_or(LATE_JOIN_ANYTIME.to_string());
+
+    if late_join == LATE_JOIN_ANYTIME || late_join == LATE_JOIN_FIRST_LOCK {
+        return Ok((max_players, late_join, 0));
+    }
+
+    if late_join == LATE_JOIN_AFTER_DROPS {
+        let late_join_drops = late_join_drops.unwrap_or(0);
+        if !(1..=MAX_LATE_JOIN_DROPS).contains(&late_join_drops) {
+            return Err(anyhow::anyhow!(
+                "Late joins must close after 1 to {MAX_LATE_JOIN_DROPS} drops"
+            ))?;
+        }
+
+        return Ok((max_players, late_join, late_join_drops));
+    }
+
+    return Err(anyhow::anyhow!("Unknown late join policy: {late_join}"))?;
+}
@@ -0,0 +1,199 @@
+use std::time::SystemTime;
+
+use super::{utils, Html};
+
+use crate::{
+    models::{Game, GameInvite, User},
+    prelude::*,
+};
+
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get, post},
+    Form, Router,
+};
+use nanoid::nanoid;
+use serde::Deserialize;
+use tower_sessions::Session;
+
+const MAX_INVITES: usize = 500;
+const INVITE_TOKEN_LENGTH: usize = 24;
+
+pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
+    return router
+        .route(
+            "/games/:game_code/access",
+            get(game_access).post(post_game_access),
+        )
+        .route("/games/:game_code/invite-link", post(post_invite_link))
+        .route("/games/:game_code/invites", post(post_game_invite))
+        .route("/games/:game_code/invites/:id", delete(delete_game_invite));
+}
+
+#[derive(Template)]
+#[template(path = "game-access.html")]
+struct GameAccessTemplate {
+    user: User,
+    game: Game,
+    invites: Vec<GameInvite>,
+    base_uri: String,
+}
+
+// Like co-hosts, only the host decides who gets in, co-hosts just work the approvals
+async fn game_access(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    let invites: Vec<GameInvite> =
+        sqlx::query_as("SELECT * FROM game_invites WHERE game_code = $1 ORDER BY twitch_login ASC")
+            .bind(&game.game_code)
+            .fetch_all(&state.db)
+            .await?;
+
+    return Ok(Html(GameAccessTemplate {
+        user,
+        game,
+        invites,
+        base_uri: state.cfg.server_host_uri.clone(),
+    })
+    .into_response());
+}
+
+#[derive(Deserialize)]
+struct GameAccessForm {
+    invite_only: Option<String>,
+    require_approval: Option<String>,
+    allow_guests: Option<String>,
+}
+
+async fn post_game_access(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<GameAccessForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    let invite_only = form.invite_only.is_some();
+    let require_approval = form.require_approval.is_some();
+    let allow_guests = form.allow_guests.is_some();
+
+    // The link is made the first time the game goes invite-only and kept until it's reset
+    let invite_token = match (invite_only, game.invite_token) {
+        (true, None) => Some(nanoid!(INVITE_TOKEN_LENGTH)),
+        (_, invite_token) => invite_token,
+    };
+
+    sqlx::query(
+        "UPDATE games SET invite_only = $1, require_approval = $2, invite_token = $3, allow_guests = $4 WHERE game_code = $5",
+    )
+    .bind(&invite_only)
+    .bind(&require_approval)
+    .bind(&invite_token)
+    .bind(&allow_guests)
+    .bind(&game.game_code)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Redirect::to(&format!("/games/{}/access", game.game_code)).into_response());
+}
+
+// A new token stops the old link from working for anyone who hasn't joined yet
+async fn post_invite_link(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    sqlx::query("UPDATE games SET invite_token = $1 WHERE game_code = $2")
+        .bind(nanoid!(INVITE_TOKEN_LENGTH))
+        .bind(&game.game_code)
+        .execute(&state.db)
+        .await?;
+
+    return Ok(Redirect::to(&format!("/games/{}/access", game.game_code)).into_response());
+}
+
+#[derive(Deserialize)]
+struct InviteForm {
+    login: String,
+}
+
+async fn post_game_invite(
+    Path(game_code): Path<String>,
+    session: Session,
+    State(state): State<AppState>,
+    Form(form): Form<InviteForm>,
+) -> Result<Response> {
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+    let login = utils::parse_login(&form.login)?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM game_invites WHERE game_code = $1")
+        .bind(&game.game_code)
+        .fetch_one(&state.db)
+        .await?;
+
+    if count as usize >= MAX_INVITES {
+        return Err(anyhow::anyhow!(
+            "Too many invites, the limit is {MAX_INVITES}"
+        ))?;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    sqlx::query(
+        "INSERT INTO game_invites (game_code, twitch_login, created_at) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+    )
+    .bind(&game.game_code)
+    .bind(&login)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    return Ok(Redirect::to(&format!("/games/{}/access", game.game_code)).into_response());
+}
+
+async fn delete_game_invite(
+    Path((game_code, id)): Path<(String, u64)>,
+    session: Session,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    let id = id as i64;
+    let session_id = utils::session_id(&session)?;
+    let (user, _) = utils::require_user(&state, &session_id).await?.split();
+
+    let game = utils::find_owned_game(&state, &game_code, &user).await?;
+
+    let res = sqlx::query("DELETE FROM game_invites WHERE game_invite_id = $1 AND game_code = $2")
+        .bind(&id)
+        .bind(&game.game_code)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    return Ok("".into_response());
+}
@@ -1,9 +1,20 @@
+mod account;
+mod auth;
+mod blocklist;
+mod cohost;
 mod game;
 mod game_template;
-mod twitch;
+mod invite;
 mod utils;
 
-use crate::{models::User, prelude::*};
+pub use game::{
+    publish_host_action, refresh_roll_seed, start_game, try_finish_game, update_round_lock,
+};
+
+use crate::{
+    models::{User, PROVIDER_OIDC},
+    prelude::*,
+};
 
 use askama::Template;
 use axum::{extract::State, response::IntoResponse, routing::get, Router};
@@ -13,8 +24,12 @@ use tower_sessions::Session;
 
 pub fn add_routes(router: Router<AppState>) -> Router<AppState> {
     let router = game::add_routes(router);
+    let router = account::add_routes(router);
+    let router = cohost::add_routes(router);
+    let router = blocklist::add_routes(router);
+    let router = invite::add_routes(router);
     let router = game_template::add_routes(router);
-    let router = twitch::add_routes(router);
+    let router = auth::add_routes(router);
 
     return router.route("/", get(index)).route(
         "/health",
@@ -52,6 +67,7 @@ impl<T: Template> IntoResponse for Html<T> {
 #[template(path = "index.html")]
 struct IndexTemplate {
     user: Option<User>,
+    oidc_name: Option<String>,
 }
 
 async fn index(session: Session, State(state): State<AppState>) -> Result<impl IntoResponse> {
@@ -63,5 +79,11 @@ async fn index(session: Session, State(state): State<AppState>) -> Result<impl I
         return user;
     });
 
-    return Ok(IndexTemplate { user });
+    return Ok(IndexTemplate {
+        user,
+        oidc_name: state
+            .auth_providers
+            .get(PROVIDER_OIDC)
+            .map(|provider| provider.display_name().to_string()),
+    });
 }
@@ -1,4 +1,7 @@
-use crate::{models::SessionAuthWithUser, prelude::*};
+use crate::{
+    models::{Game, SessionAuthWithUser, User},
+    prelude::*,
+};
 
 use sqlx;
 use tower_sessions::Session;
@@ -25,3 +28,77 @@ pub async fn require_user(state: &AppState, session_id: &str) -> Result<SessionA
     let found = found.ok_or_else(|| anyhow::anyhow!("Unauthenticated"))?;
     return Ok(found);
 }
+
+// Twitch logins are lowercase letters, digits and underscores, people tend to paste them with an @
+pub fn parse_login(login: &str) -> Result<String> {
+    let login = login.trim().trim_start_matches('@').to_lowercase();
+
+    if login.is_empty() || login.len() > 25 {
+        return Err(anyhow::anyhow!("Invalid Twitch login"))?;
+    }
+
+    if !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(anyhow::anyhow!("Invalid Twitch login"))?;
+    }
+
+    return Ok(login);
+}
+
+// Matches games the user in $2 can run: their own, ones they co-host, and every game
+// of a host who keeps them on their standing co-host list
+pub const HOST_ACCESS: &str = r#"(
+    games.user_id = $2 OR
+    EXISTS (
+        SELECT 1
+        FROM game_cohosts
+        WHERE game_cohosts.game_code = games.game_code AND game_cohosts.cohost_user_id = $2
+    ) OR
+    EXISTS (
+        SELECT 1
+        FROM user_cohosts
+        WHERE user_cohosts.host_user_id = games.user_id AND user_cohosts.cohost_user_id = $2
+    )
+)"#;
+
+/// The game if the user created it. Invites and co-hosts are only managed by the owner
+pub async fn find_owned_game(state: &AppState, game_code: &str, user: &User) -> Result<Game> {
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> =
+        sqlx::query_as("SELECT * FROM games WHERE game_code = $1 AND user_id = $2 LIMIT 1")
+            .bind(&game_code)
+            .bind(&user.user_id)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    return Ok(game);
+}
+
+/// The game if the user can run it, as its owner or one of its co-hosts
+pub async fn find_host_access_game(state: &AppState, game_code: &str, user: &User) -> Result<Game> {
+    if game_code.trim().is_empty() {
+        return Err(anyhow::anyhow!("Missing game_code"))?;
+    }
+    let game_code = game_code.to_lowercase();
+
+    let game: Option<Game> = sqlx::query_as(&format!(
+        "SELECT * FROM games WHERE game_code = $1 AND {HOST_ACCESS} LIMIT 1"
+    ))
+    .bind(&game_code)
+    .bind(&user.user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(game) = game else {
+        return Err(anyhow::anyhow!("Game not found"))?;
+    };
+
+    return Ok(game);
+}
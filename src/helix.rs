@@ -0,0 +1,334 @@
+use crate::prelude::*;
+
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+// Long enough to absorb a rush of joins, short enough that a new sub gets in within a minute
+pub const ELIGIBILITY_CACHE_TTL: Duration = Duration::from_secs(60);
+const ELIGIBILITY_CACHE_MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EligibilityRule {
+    Follower,
+    Subscriber,
+    Vip,
+}
+
+/// The Helix calls used to check who can join a game. The base URL comes from config,
+/// so a local stub server can stand in for Twitch
+#[async_trait]
+pub trait HelixClient: Send + Sync {
+    async fn check(
+        &self,
+        access_token: &str,
+        broadcaster_id: &str,
+        user_id: &str,
+        rule: EligibilityRule,
+    ) -> Result<bool>;
+}
+
+pub struct TwitchHelixClient {
+    http: reqwest::Client,
+    base_url: String,
+    client_id: String,
+}
+
+// Every endpoint used here returns the matching relationship, or nothing when there isn't one
+#[derive(Deserialize)]
+struct HelixPage {
+    data: Vec<serde_json::Value>,
+}
+
+impl TwitchHelixClient {
+    pub fn new(base_url: &str, client_id: &str) -> Self {
+        return Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client_id: client_id.to_string(),
+        };
+    }
+}
+
+#[async_trait]
+impl HelixClient for TwitchHelixClient {
+    async fn check(
+        &self,
+        access_token: &str,
+        broadcaster_id: &str,
+        user_id: &str,
+        rule: EligibilityRule,
+    ) -> Result<bool> {
+        let (path, query) = match rule {
+            EligibilityRule::Follower => (
+                "channels/followers",
+                [("broadcaster_id", broadcaster_id), ("user_id", user_id)],
+            ),
+            EligibilityRule::Subscriber => (
+                "subscriptions",
+                [("broadcaster_id", broadcaster_id), ("user_id", user_id)],
+            ),
+            EligibilityRule::Vip => (
+                "channels/vips",
+                [("broadcaster_id", broadcaster_id), ("user_id", user_id)],
+            ),
+        };
+
+        let response = self
+            .http
+            .get(format!("{}/{path}", self.base_url))
+            .query(&query)
+            .header("Client-ID", &self.client_id)
+            .bearer_auth(access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let page: HelixPage = serde_json::from_slice(&response.bytes().await?)?;
+
+        return Ok(!page.data.is_empty());
+    }
+}
+
+// Keyed by broadcaster, user and rule
+type EligibilityCache = HashMap<(String, String, EligibilityRule), (bool, Instant)>;
+
+/// Remembers answers for a short while so a channel's viewers piling into a game don't
+/// each cost a Helix call per rule
+pub struct CachedHelixClient<C: HelixClient> {
+    inner: C,
+    ttl: Duration,
+    cache: RwLock<EligibilityCache>,
+}
+
+impl<C: HelixClient> CachedHelixClient<C> {
+    pub fn new(inner: C, ttl: Duration) -> Self {
+        return Self {
+            inner,
+            ttl,
+            cache: RwLock::new(HashMap::new()),
+        };
+    }
+}
+
+#[async_trait]
+impl<C: HelixClient> HelixClient for CachedHelixClient<C> {
+    async fn check(
+        &self,
+        access_token: &str,
+        broadcaster_id: &str,
+        user_id: &str,
+        rule: EligibilityRule,
+    ) -> Result<bool> {
+        let key = (broadcaster_id.to_string(), user_id.to_string(), rule);
+
+        if let Some((found, checked_at)) = self.cache.read().unwrap().get(&key) {
+            if checked_at.elapsed() < self.ttl {
+                return Ok(*found);
+            }
+        }
+
+        let found = self
+            .inner
+            .check(access_token, broadcaster_id, user_id, rule)
+            .await?;
+
+        let mut cache = self.cache.write().unwrap();
+        if cache.len() >= ELIGIBILITY_CACHE_MAX_ENTRIES {
+            cache.retain(|_, (_, checked_at)| checked_at.elapsed() < self.ttl);
+        }
+        cache.insert(key, (found, Instant::now()));
+
+        return Ok(found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        net::TcpListener,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{
+        extract::Query,
+        http::{HeaderMap, StatusCode},
+        routing::get,
+        Json, Router,
+    };
+
+    const CLIENT_ID: &str = "test-client";
+    const ACCESS_TOKEN: &str = "test-token";
+    const BROADCASTER_ID: &str = "100";
+    const FOLLOWER_ID: &str = "200";
+
+    // Answers like Helix does: the relationship when there is one, an empty page when there isn't,
+    // and a 401 when the request isn't made with the app's client id and the host's token
+    async fn relationship(
+        headers: HeaderMap,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> std::result::Result<Json<serde_json::Value>, StatusCode> {
+        let authorized = headers.get("Client-ID").and_then(|v| v.to_str().ok()) == Some(CLIENT_ID)
+            && headers.get("Authorization").and_then(|v| v.to_str().ok())
+                == Some(&format!("Bearer {ACCESS_TOKEN}"));
+        if !authorized {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        let found = query.get("broadcaster_id").map(String::as_str) == Some(BROADCASTER_ID)
+            && query.get("user_id").map(String::as_str) == Some(FOLLOWER_ID);
+
+        if !found {
+            return Ok(Json(serde_json::json!({ "data": [] })));
+        }
+
+        return Ok(Json(
+            serde_json::json!({ "data": [{ "user_id": FOLLOWER_ID }] }),
+        ));
+    }
+
+    fn serve_stub() -> String {
+        let router = Router::new()
+            .route("/channels/followers", get(relationship))
+            .route("/subscriptions", get(relationship));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        return format!("http://{addr}/");
+    }
+
+    #[tokio::test]
+    async fn twitch_client_reads_relationships() {
+        let client = TwitchHelixClient::new(&serve_stub(), CLIENT_ID);
+
+        let followed = client
+            .check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Follower,
+            )
+            .await
+            .unwrap();
+        assert!(followed);
+
+        let not_followed = client
+            .check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                "300",
+                EligibilityRule::Follower,
+            )
+            .await
+            .unwrap();
+        assert!(!not_followed);
+
+        let subscribed = client
+            .check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Subscriber,
+            )
+            .await
+            .unwrap();
+        assert!(subscribed);
+    }
+
+    #[tokio::test]
+    async fn twitch_client_fails_on_errors() {
+        let client = TwitchHelixClient::new(&serve_stub(), CLIENT_ID);
+
+        // Rejected tokens and missing endpoints are errors, not a "no"
+        let rejected = client
+            .check(
+                "expired",
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Follower,
+            )
+            .await;
+        assert!(rejected.is_err());
+
+        let missing = client
+            .check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Vip,
+            )
+            .await;
+        assert!(missing.is_err());
+    }
+
+    struct CountingClient {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HelixClient for CountingClient {
+        async fn check(
+            &self,
+            _access_token: &str,
+            _broadcaster_id: &str,
+            user_id: &str,
+            _rule: EligibilityRule,
+        ) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            return Ok(user_id == FOLLOWER_ID);
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_client_expires_answers() {
+        let ttl = Duration::from_millis(50);
+        let client = CachedHelixClient::new(
+            CountingClient {
+                calls: AtomicUsize::new(0),
+            },
+            ttl,
+        );
+        let check = || {
+            client.check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Follower,
+            )
+        };
+
+        assert!(check().await.unwrap());
+        assert!(check().await.unwrap());
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 1);
+
+        // Other rules and users are cached on their own
+        client
+            .check(
+                ACCESS_TOKEN,
+                BROADCASTER_ID,
+                FOLLOWER_ID,
+                EligibilityRule::Vip,
+            )
+            .await
+            .unwrap();
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(ttl * 2).await;
+
+        assert!(check().await.unwrap());
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 3);
+    }
+}
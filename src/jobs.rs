@@ -0,0 +1,204 @@
+use crate::{
+    controllers,
+    models::{Game, GameStatus, FINISHED_REASON_ABANDONED},
+    prelude::*,
+    pubsub::{HostAction, HostActionType},
+};
+
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+pub async fn run(state: AppState) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last_sweep: Option<Instant> = None;
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = start_scheduled_games(&state).await {
+            dbg!(e);
+        }
+
+        if let Err(e) = lock_expired_games(&state).await {
+            dbg!(e);
+        }
+
+        if last_sweep.is_none_or(|last_sweep| last_sweep.elapsed() >= SWEEP_INTERVAL) {
+            last_sweep = Some(Instant::now());
+
+            if let Err(e) = finish_abandoned_games(&state).await {
+                dbg!(e);
+            }
+
+            if let Err(e) = drop_finished_broadcasts(&state).await {
+                dbg!(e);
+            }
+        }
+    }
+}
+
+async fn start_scheduled_games(state: &AppState) -> Result {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_millis() as i64;
+
+    let games: Vec<Game> =
+        sqlx::query_as("SELECT * FROM games WHERE status = $1 AND start_at_ms <= $2")
+            .bind(GameStatus::Lobby)
+            .bind(&now)
+            .fetch_all(&state.db)
+            .await?;
+
+    // start_game only goes ahead for whichever instance moves the game out of the lobby first
+    for mut game in games {
+        if let Err(e) = controllers::start_game(state, &mut game, None).await {
+            eprintln!("Couldn't start game {}: {e}", game.game_code);
+        }
+    }
+
+    return Ok(());
+}
+
+async fn lock_expired_games(state: &AppState) -> Result {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_millis() as i64;
+
+    let game_codes: Vec<String> = sqlx::query_scalar(
+        "SELECT game_code FROM games WHERE status = $1 AND is_locked = false AND lock_at_ms <= $2",
+    )
+    .bind(GameStatus::Active)
+    .bind(&now)
+    .fetch_all(&state.db)
+    .await?;
+
+    // One game failing mustn't hold up the rest, it gets another go on the next tick
+    for game_code in game_codes {
+        if let Err(e) = lock_expired_game(state, &game_code, now).await {
+            eprintln!("Couldn't lock game {game_code}: {e}");
+        }
+    }
+
+    return Ok(());
+}
+
+// Claiming the row in the UPDATE keeps two instances from publishing the same lock. The claim
+// commits together with the round and seed, so a failure leaves the game unlocked to retry
+async fn lock_expired_game(state: &AppState, game_code: &str, now: i64) -> Result {
+    let mut tx = state.db.begin().await?;
+
+    let game: Option<Game> = sqlx::query_as(
+        "UPDATE games SET is_locked = true, locked_at_ms = lock_at_ms, lock_at_ms = NULL WHERE game_code = $1 AND status = $2 AND is_locked = false AND lock_at_ms <= $3 RETURNING *",
+    )
+    .bind(game_code)
+    .bind(GameStatus::Active)
+    .bind(&now)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(mut game) = game else {
+        return Ok(());
+    };
+
+    controllers::update_round_lock(&mut *tx, &game, None).await?;
+
+    // Same as locking by hand: games with a drop table commit to a seed for the roll
+    controllers::refresh_roll_seed(&mut *tx, &mut game).await?;
+
+    tx.commit().await?;
+
+    controllers::publish_host_action(
+        state,
+        HostAction {
+            game_code: game_code.to_string(),
+            typ: HostActionType::Lock,
+            actor: None,
+        },
+    )
+    .await?;
+
+    return Ok(());
+}
+
+// A game counts as active while players are joining, guessing or the host is opening rounds
+async fn finish_abandoned_games(state: &AppState) -> Result {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+    let cutoff = now - state.cfg.abandoned_game_after_secs;
+    let cutoff_ms = cutoff * 1000;
+
+    let games: Vec<Game> = sqlx::query_as(
+        r#"
+SELECT *
+FROM games
+WHERE
+    status IN ($1, $2) AND
+    active_at < $3 AND
+    COALESCE((SELECT MAX(received_at_ms) FROM player_guesses WHERE player_guesses.game_code = games.game_code), 0) < $4 AND
+    COALESCE((SELECT MAX(opened_at_ms) FROM game_rounds WHERE game_rounds.game_code = games.game_code), 0) < $4
+"#,
+    )
+    .bind(GameStatus::Active)
+    .bind(GameStatus::Paused)
+    .bind(&cutoff)
+    .bind(&cutoff_ms)
+    .fetch_all(&state.db)
+    .await?;
+
+    for mut game in games {
+        if let Err(e) = finish_abandoned_game(state, &mut game).await {
+            eprintln!("Couldn't finish abandoned game {}: {e}", game.game_code);
+        }
+    }
+
+    return Ok(());
+}
+
+async fn finish_abandoned_game(state: &AppState, game: &mut Game) -> Result {
+    controllers::try_finish_game(state, game, FINISHED_REASON_ABANDONED, None).await?;
+
+    // Nobody is around to play out sudden death, so finishing again settles on the tied players
+    if game.status != GameStatus::Finished {
+        controllers::try_finish_game(state, game, FINISHED_REASON_ABANDONED, None).await?;
+    }
+
+    return Ok(());
+}
+
+// Channels stick around after a game finishes until the last page listening to them closes
+async fn drop_finished_broadcasts(state: &AppState) -> Result {
+    let game_codes: Vec<String> = {
+        let broadcasts = &*state.game_broadcasts.read().unwrap();
+
+        broadcasts
+            .iter()
+            .filter(|(_, broadcast)| {
+                broadcast.to_host.receiver_count() == 0
+                    && broadcast.to_players.receiver_count() == 0
+            })
+            .map(|(game_code, _)| game_code.clone())
+            .collect()
+    };
+
+    if game_codes.is_empty() {
+        return Ok(());
+    }
+
+    let finished: Vec<String> =
+        sqlx::query_scalar("SELECT game_code FROM games WHERE game_code = ANY($1) AND status = $2")
+            .bind(&game_codes)
+            .bind(GameStatus::Finished)
+            .fetch_all(&state.db)
+            .await?;
+
+    let broadcasts = &mut *state.game_broadcasts.write().unwrap();
+
+    for game_code in finished {
+        broadcasts.remove(&game_code);
+    }
+
+    return Ok(());
+}
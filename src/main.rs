@@ -1,17 +1,23 @@
+mod auth;
 mod config;
 mod controllers;
+mod helix;
 mod init;
+mod jobs;
 mod models;
 mod pubsub;
+mod rate_limit;
 mod result;
 
 use std::{
     collections::HashMap,
+    net::SocketAddr,
     sync::{Arc, RwLock},
     time::Duration,
 };
 
 use async_trait::async_trait;
+use auth::{init_auth_providers, AuthProviders};
 use axum::{
     error_handling::HandleErrorLayer,
     extract::DefaultBodyLimit,
@@ -21,10 +27,15 @@ use axum::{
     Router, Server,
 };
 use chrono::DateTime;
+use helix::{CachedHelixClient, HelixClient, TwitchHelixClient, ELIGIBILITY_CACHE_TTL};
 use lazy_static::lazy_static;
-use models::{ChatMessage, SessionAuth, User};
+use models::{ChatMessage, GameStatus, SessionAuth, User};
 use nanoid::nanoid;
 use pubsub::{HostAction, PlayerAction, PubSubClients};
+use rate_limit::{
+    RateLimiter, GUEST_CLIENT_JOIN_LIMIT, GUEST_GUESS_LIMIT, GUEST_GUESS_WINDOW, GUEST_JOIN_LIMIT,
+    GUEST_JOIN_WINDOW,
+};
 use reqwest::{header::LOCATION, Method};
 use result::AppError;
 use s3::Bucket;
@@ -77,6 +88,11 @@ pub struct AppState {
     pub bucket: Bucket,
     pub db: PgPool,
     pub pubsub: Arc<PubSubClients>,
+    pub helix: Arc<dyn HelixClient>,
+    pub auth_providers: Arc<AuthProviders>,
+    pub guest_joins: Arc<RateLimiter>,
+    pub guest_client_joins: Arc<RateLimiter>,
+    pub guest_guesses: Arc<RateLimiter>,
     pub game_broadcasts: Arc<RwLock<HashMap<String, GameBroadcast>>>,
 }
 
@@ -98,6 +114,14 @@ async fn main() -> Result {
     let db = init::db::init_pg_pool(&cfg).await?;
     let session_store = init::session::init_session_store(&cfg, db.clone()).await?;
     let pubsub = init::pubsub::init_pubsub(&cfg).await?;
+    let helix: Arc<dyn HelixClient> = Arc::new(CachedHelixClient::new(
+        TwitchHelixClient::new(&cfg.twitch_helix_url, cfg.twitch_client_id.as_str()),
+        ELIGIBILITY_CACHE_TTL,
+    ));
+    let auth_providers = Arc::new(init_auth_providers(&cfg));
+    let guest_joins = Arc::new(RateLimiter::new(GUEST_JOIN_LIMIT, GUEST_JOIN_WINDOW));
+    let guest_client_joins = Arc::new(RateLimiter::new(GUEST_CLIENT_JOIN_LIMIT, GUEST_JOIN_WINDOW));
+    let guest_guesses = Arc::new(RateLimiter::new(GUEST_GUESS_LIMIT, GUEST_GUESS_WINDOW));
     let game_broadcasts: Arc<RwLock<HashMap<String, GameBroadcast>>> =
         Arc::new(RwLock::new(HashMap::new()));
 
@@ -161,9 +185,22 @@ async fn main() -> Result {
         bucket,
         db,
         pubsub,
+        helix,
+        auth_providers,
+        guest_joins,
+        guest_client_joins,
+        guest_guesses,
         game_broadcasts,
     };
 
+    {
+        let state = state.clone();
+
+        tokio::spawn(async move {
+            jobs::run(state).await;
+        });
+    }
+
     let session_service = ServiceBuilder::new()
         .layer(HandleErrorLayer::new(|_| async {
             return StatusCode::BAD_REQUEST;
@@ -208,7 +245,7 @@ async fn main() -> Result {
         .layer(middleware::from_fn(add_redirect_header));
 
     Server::bind(&addr)
-        .serve(router.into_make_service())
+        .serve(router.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
 
     return Ok(());
@@ -267,9 +304,11 @@ async fn add_redirect_header<B>(req: Request<B>, next: Next<B>) -> Response {
 }
 
 async fn send_chat_messages(cfg: Arc<Config>, db: PgPool) -> Result {
+    // Messages for paused games stay queued until the host resumes
     let count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM chat_messages WHERE lock_id IS NULL AND sent = false",
+        "SELECT COUNT(*) FROM chat_messages WHERE lock_id IS NULL AND sent = false AND game_code NOT IN (SELECT game_code FROM games WHERE status = $1)",
     )
+    .bind(GameStatus::Paused)
     .fetch_one(&db)
     .await?;
 
@@ -279,8 +318,9 @@ async fn send_chat_messages(cfg: Arc<Config>, db: PgPool) -> Result {
 
     let lock_id = nanoid!(64);
 
-    sqlx::query("UPDATE chat_messages SET lock_id = ? WHERE lock_id IS NULL AND sent = false")
+    sqlx::query("UPDATE chat_messages SET lock_id = ? WHERE lock_id IS NULL AND sent = false AND game_code NOT IN (SELECT game_code FROM games WHERE status = ?)")
         .bind(&lock_id)
+        .bind(GameStatus::Paused)
         .execute(&db)
         .await?;
 
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameBan {
+    pub game_ban_id: i32,
+    pub game_code: String,
+    pub user_id: String,
+    pub banned_by: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameBanWithUser {
+    pub game_ban_id: i32,
+    pub game_code: String,
+    pub user_id: String,
+    pub banned_by: Option<String>,
+    pub created_at: i64,
+
+    pub username: String,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct UserBlock {
+    pub user_block_id: i32,
+    pub host_user_id: String,
+    pub twitch_login: String,
+    pub created_at: i64,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameCohost {
+    pub game_cohost_id: i32,
+    pub game_code: String,
+    pub cohost_user_id: String,
+    pub twitch_login: String,
+    pub created_at: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct UserCohost {
+    pub user_cohost_id: i32,
+    pub host_user_id: String,
+    pub cohost_user_id: String,
+    pub twitch_login: String,
+    pub created_at: i64,
+}
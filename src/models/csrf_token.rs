@@ -9,4 +9,6 @@ pub struct CsrfToken {
     pub expiry: Option<i64>,
     pub redirect: Option<String>,
     pub with_chat: bool,
+    pub with_eligibility: bool,
+    pub provider: String,
 }
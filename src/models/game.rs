@@ -1,24 +1,156 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
-use sqlx;
+use sqlx::{
+    self,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    Postgres,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GameStatus {
+    Lobby,
+    Active,
+    Paused,
+    Finished,
+}
+
+impl GameStatus {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            GameStatus::Lobby => "LOBBY",
+            GameStatus::Active => "ACTIVE",
+            GameStatus::Paused => "PAUSED",
+            GameStatus::Finished => "FINISHED",
+        };
+    }
+
+    // Games only move forward apart from pausing, a lobby can be closed without ever starting
+    pub fn can_become(&self, next: GameStatus) -> bool {
+        return matches!(
+            (self, next),
+            (GameStatus::Lobby, GameStatus::Active)
+                | (GameStatus::Lobby, GameStatus::Finished)
+                | (GameStatus::Active, GameStatus::Paused)
+                | (GameStatus::Active, GameStatus::Finished)
+                | (GameStatus::Paused, GameStatus::Active)
+                | (GameStatus::Paused, GameStatus::Finished)
+        );
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(self.as_str());
+    }
+}
+
+impl FromStr for GameStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "LOBBY" => Ok(GameStatus::Lobby),
+            "ACTIVE" => Ok(GameStatus::Active),
+            "PAUSED" => Ok(GameStatus::Paused),
+            "FINISHED" => Ok(GameStatus::Finished),
+            _ => Err(anyhow::anyhow!("Unknown game status: {s}")),
+        };
+    }
+}
+
+// Stored as the plain VARCHAR status column
+impl sqlx::Type<Postgres> for GameStatus {
+    fn type_info() -> PgTypeInfo {
+        return <&str as sqlx::Type<Postgres>>::type_info();
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        return <&str as sqlx::Type<Postgres>>::compatible(ty);
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for GameStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as sqlx::Decode<Postgres>>::decode(value)?;
+        return Ok(s.parse()?);
+    }
+}
+
+impl sqlx::Encode<'_, Postgres> for GameStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        return <&str as sqlx::Encode<Postgres>>::encode_by_ref(&self.as_str(), buf);
+    }
+}
+
+pub const SPEED_BONUS_NONE: &str = "NONE";
+pub const SPEED_BONUS_FIRST_N: &str = "FIRST_N";
+pub const SPEED_BONUS_DECAY: &str = "DECAY";
+
+pub const TIE_BREAK_NONE: &str = "NONE";
+pub const TIE_BREAK_TIME: &str = "TIME";
+pub const TIE_BREAK_CHANGES: &str = "CHANGES";
+pub const TIE_BREAK_RANDOM: &str = "RANDOM";
+pub const TIE_BREAK_SUDDEN_DEATH: &str = "SUDDEN_DEATH";
+
+pub const LATE_JOIN_ANYTIME: &str = "ANYTIME";
+pub const LATE_JOIN_AFTER_DROPS: &str = "AFTER_DROPS";
+pub const LATE_JOIN_FIRST_LOCK: &str = "FIRST_LOCK";
 
-pub const GAME_STATUS_ACTIVE: &str = "ACTIVE";
-pub const GAME_STATUS_FINISHED: &str = "FINISHED";
+pub const FINISHED_REASON_HOST: &str = "HOST";
+pub const FINISHED_REASON_SUDDEN_DEATH: &str = "SUDDEN_DEATH";
+pub const FINISHED_REASON_ABANDONED: &str = "ABANDONED";
 
 #[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
 pub struct Game {
     pub game_code: String,
     pub user_id: String,
 
-    pub status: String,
+    pub status: GameStatus,
     pub created_at: i64,
     pub active_at: i64,
+    pub start_at_ms: Option<i64>,
+    pub paused_at_ms: Option<i64>,
+    pub finished_reason: Option<String>,
+    pub finished_notice_seen: bool,
+    pub finished_by: Option<String>,
 
     pub name: String,
     pub auto_lock: bool,
+    pub max_guesses: i32,
+    pub stream_delay: i32,
+    pub speed_bonus: String,
+    pub speed_bonus_points: i32,
+    pub speed_bonus_first_n: i32,
+    pub speed_bonus_decay_secs: i32,
+    pub tie_break: String,
     pub reward_message: Option<String>,
     pub total_reward_message: Option<String>,
 
     pub is_locked: bool,
+    pub lock_at_ms: Option<i64>,
+    pub locked_at_ms: Option<i64>,
+    pub unlocked_at_ms: Option<i64>,
+    pub sudden_death: bool,
+    pub roll_seed: Option<i64>,
+    pub roll_seed_hash: Option<String>,
+
+    pub invite_only: bool,
+    pub invite_token: Option<String>,
+    pub require_approval: bool,
+    pub allow_guests: bool,
+
+    pub eligible_followers: bool,
+    pub eligible_subscribers: bool,
+    pub eligible_vips: bool,
+
+    pub max_players: Option<i32>,
+    pub late_join: String,
+    pub late_join_drops: i32,
+    pub late_join_catch_up: bool,
 }
 
 #[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -26,25 +158,44 @@ pub struct GameWithJoinedSummary {
     pub game_code: String,
     pub user_id: String,
 
-    pub status: String,
+    pub status: GameStatus,
     pub created_at: i64,
     pub active_at: i64,
+    pub start_at_ms: Option<i64>,
+    pub paused_at_ms: Option<i64>,
+    pub finished_reason: Option<String>,
+    pub finished_notice_seen: bool,
+    pub finished_by: Option<String>,
 
     pub name: String,
     pub auto_lock: bool,
+    pub max_guesses: i32,
+    pub stream_delay: i32,
+    pub speed_bonus: String,
+    pub speed_bonus_points: i32,
+    pub speed_bonus_first_n: i32,
+    pub speed_bonus_decay_secs: i32,
+    pub tie_break: String,
     pub reward_message: Option<String>,
     pub total_reward_message: Option<String>,
 
     pub is_locked: bool,
+    pub lock_at_ms: Option<i64>,
+    pub locked_at_ms: Option<i64>,
+    pub unlocked_at_ms: Option<i64>,
+    pub sudden_death: bool,
+    pub roll_seed: Option<i64>,
+    pub roll_seed_hash: Option<String>,
 
     pub players_count: Option<i64>,
     pub winners_count: Option<i64>,
 
-    pub winning_points: Option<i32>,
+    pub winning_correct_guesses: Option<i64>,
     pub total_drops: Option<i64>,
 
     pub is_winner: Option<bool>,
     pub points: i32,
+    pub correct_guesses: i64,
     pub host: String,
 }
 
@@ -53,20 +204,38 @@ pub struct GameWithHostedSummary {
     pub game_code: String,
     pub user_id: String,
 
-    pub status: String,
+    pub status: GameStatus,
     pub created_at: i64,
     pub active_at: i64,
+    pub start_at_ms: Option<i64>,
+    pub paused_at_ms: Option<i64>,
+    pub finished_reason: Option<String>,
+    pub finished_notice_seen: bool,
+    pub finished_by: Option<String>,
 
     pub name: String,
     pub auto_lock: bool,
+    pub max_guesses: i32,
+    pub stream_delay: i32,
+    pub speed_bonus: String,
+    pub speed_bonus_points: i32,
+    pub speed_bonus_first_n: i32,
+    pub speed_bonus_decay_secs: i32,
+    pub tie_break: String,
     pub reward_message: Option<String>,
     pub total_reward_message: Option<String>,
 
     pub is_locked: bool,
+    pub lock_at_ms: Option<i64>,
+    pub locked_at_ms: Option<i64>,
+    pub unlocked_at_ms: Option<i64>,
+    pub sudden_death: bool,
+    pub roll_seed: Option<i64>,
+    pub roll_seed_hash: Option<String>,
 
     pub players_count: Option<i64>,
     pub winners_count: Option<i64>,
 
-    pub winning_points: Option<i32>,
+    pub winning_correct_guesses: Option<i64>,
     pub total_drops: Option<i64>,
 }
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+pub const EVENT_SOURCE_HOST: &str = "HOST";
+pub const EVENT_SOURCE_PLAYER: &str = "PLAYER";
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameEvent {
+    pub game_event_id: i32,
+    pub game_code: String,
+    pub source: String,
+    pub event_type: String,
+    pub actor: Option<String>,
+    pub payload: String,
+    pub created_at_ms: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameEventWithActor {
+    pub game_event_id: i32,
+    pub game_code: String,
+    pub source: String,
+    pub event_type: String,
+    pub actor: Option<String>,
+    pub payload: String,
+    pub created_at_ms: i64,
+
+    pub actor_username: Option<String>,
+}
+
+impl GameEventWithActor {
+    /// Fields of the action, None for actions that carry no data
+    pub fn details(&self) -> Option<String> {
+        let payload: serde_json::Value = serde_json::from_str(&self.payload).ok()?;
+
+        return payload
+            .as_object()
+            .and_then(|fields| fields.get(&self.event_type))
+            .map(|fields| fields.to_string());
+    }
+}
@@ -1,4 +1,7 @@
+use rand::{distributions::WeightedIndex, prelude::Distribution, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx;
 
 #[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -10,6 +13,8 @@ pub struct GameItem {
     pub image: Option<String>,
 
     pub enabled: bool,
+    pub stock: Option<i32>,
+    pub drop_weight: Option<i32>,
 }
 
 #[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -21,6 +26,28 @@ pub struct GameItemWithGuessCount {
     pub image: Option<String>,
 
     pub enabled: bool,
+    pub stock: Option<i32>,
+    pub drop_weight: Option<i32>,
 
     pub guess_count: Option<i32>,
 }
+
+// Published while guessing is locked, so viewers can check the revealed seed wasn't swapped
+pub fn roll_seed_hash(seed: i64) -> String {
+    return hex::encode(Sha256::digest(seed.to_string()));
+}
+
+// Picks between the enabled items with a drop weight, in id order, so the same seed always rolls the same item
+pub fn roll_drop(seed: i64, items: &[GameItem]) -> Option<i64> {
+    let mut items: Vec<&GameItem> = items
+        .iter()
+        .filter(|item| item.enabled && item.drop_weight.unwrap_or(0) > 0)
+        .collect();
+    items.sort_by_key(|item| item.game_item_id);
+
+    let weights =
+        WeightedIndex::new(items.iter().map(|item| item.drop_weight.unwrap_or(0))).ok()?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+
+    return Some(items[weights.sample(&mut rng)].game_item_id);
+}
@@ -7,4 +7,9 @@ pub struct GameItemOutcome {
 
     pub game_code: String,
     pub item_id: i64,
+
+    /// Outcomes revealed together share the `outcome_id` of the first item in the group
+    pub group_id: i64,
+
+    pub roll_seed: Option<i64>,
 }
@@ -10,4 +10,6 @@ pub struct GameItemTemplate {
     pub image: Option<String>,
 
     pub start_enabled: bool,
+    pub stock: Option<i32>,
+    pub drop_weight: Option<i32>,
 }
@@ -1,3 +1,5 @@
+use super::is_guest_id;
+
 use serde::{Deserialize, Serialize};
 use sqlx;
 
@@ -9,4 +11,55 @@ pub struct GamePlayer {
     pub user_id: String,
 
     pub points: i32,
+    pub guess_changes: i32,
+    pub sudden_death: bool,
+
+    /// Set when a host kicks the player, the row is kept for the rounds and ledger that point at it
+    pub kicked_at: Option<i64>,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GamePlayerTiming {
+    pub user_id: String,
+    pub username: String,
+    pub points: i32,
+    pub bonus_points: i64,
+    pub correct_guesses: i64,
+    pub total_response_ms: Option<i64>,
+}
+
+impl GamePlayerTiming {
+    pub fn is_guest(&self) -> bool {
+        return is_guest_id(&self.user_id);
+    }
+
+    pub fn total_response_secs(&self) -> Option<String> {
+        return self
+            .total_response_ms
+            .map(|ms| format!("{:.1}s", ms as f64 / 1000.0));
+    }
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GamePlayerStanding {
+    pub game_player_id: i64,
+    pub user_id: String,
+    pub username: String,
+    pub points: i32,
+
+    /// Competition ranking, players on the same points share a rank and the next rank is skipped
+    pub rank: i64,
+    pub correct_guesses: i64,
+
+    /// Net points the host added or removed by hand
+    pub adjusted_points: i64,
+
+    /// Share of players with fewer points
+    pub percentile: i32,
+}
+
+impl GamePlayerStanding {
+    pub fn is_guest(&self) -> bool {
+        return is_guest_id(&self.user_id);
+    }
 }
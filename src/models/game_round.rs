@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+pub const ROUND_STATUS_OPEN: &str = "OPEN";
+pub const ROUND_STATUS_LOCKED: &str = "LOCKED";
+pub const ROUND_STATUS_RESOLVED: &str = "RESOLVED";
+
+pub const ROUND_CLOSED_BY_CHOOSE: &str = "CHOOSE";
+pub const ROUND_CLOSED_BY_ROLL: &str = "ROLL";
+pub const ROUND_CLOSED_BY_CLEAR: &str = "CLEAR";
+pub const ROUND_CLOSED_BY_FINISH: &str = "FINISH";
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameRound {
+    pub game_round_id: i32,
+    pub game_code: String,
+    pub status: String,
+
+    pub opened_at_ms: i64,
+    pub locked_at_ms: Option<i64>,
+    pub resolved_at_ms: Option<i64>,
+
+    pub outcome_id: Option<i64>,
+    pub closed_by: Option<String>,
+    pub locked_by: Option<String>,
+    pub resolved_by: Option<String>,
+
+    pub guesses_count: i32,
+    pub players_count: i32,
+    pub correct_count: i32,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameRoundWithItems {
+    pub game_round_id: i32,
+    pub game_code: String,
+    pub status: String,
+
+    pub opened_at_ms: i64,
+    pub locked_at_ms: Option<i64>,
+    pub resolved_at_ms: Option<i64>,
+
+    pub outcome_id: Option<i64>,
+    pub closed_by: Option<String>,
+    pub locked_by: Option<String>,
+    pub resolved_by: Option<String>,
+
+    pub guesses_count: i32,
+    pub players_count: i32,
+    pub correct_count: i32,
+
+    pub item_names: Option<String>,
+    pub resolved_by_username: Option<String>,
+}
+
+impl GameRoundWithItems {
+    pub fn open_secs(&self) -> Option<String> {
+        return self
+            .locked_at_ms
+            .or(self.resolved_at_ms)
+            .map(|closed_at| format!("{:.1}s", (closed_at - self.opened_at_ms) as f64 / 1000.0));
+    }
+}
@@ -8,6 +8,20 @@ pub struct GameTemplate {
 
     pub name: String,
     pub auto_lock: bool,
+    pub max_guesses: i32,
+    pub stream_delay: i32,
+    pub speed_bonus: String,
+    pub speed_bonus_points: i32,
+    pub speed_bonus_first_n: i32,
+    pub speed_bonus_decay_secs: i32,
+    pub tie_break: String,
+    pub eligible_followers: bool,
+    pub eligible_subscribers: bool,
+    pub eligible_vips: bool,
+    pub max_players: Option<i32>,
+    pub late_join: String,
+    pub late_join_drops: i32,
+    pub late_join_catch_up: bool,
 
     pub reward_message: Option<String>,
     pub total_reward_message: Option<String>,
@@ -0,0 +1,167 @@
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx;
+
+pub const GIVEAWAY_POOL_WINNERS: &str = "WINNERS";
+pub const GIVEAWAY_POOL_SCORERS: &str = "SCORERS";
+
+pub const GIVEAWAY_METHOD_SHA256_PICKS: &str = "SHA256_PICKS";
+/// Draws made before SHA256_PICKS, verified with the rand version they were drawn with
+pub const GIVEAWAY_METHOD_CHACHA8_SHUFFLE: &str = "CHACHA8_SHUFFLE";
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GiveawayDraw {
+    pub giveaway_draw_id: i32,
+    pub game_code: String,
+    pub pool: String,
+    pub seed: i64,
+    pub draw_count: i32,
+    pub entrants: Vec<String>,
+    pub winners: Vec<String>,
+    pub created_at: i64,
+
+    /// game_player_ids in the same order as `entrants`, the seed is applied to these
+    pub entrant_ids: Vec<i64>,
+    pub entrant_guests: Vec<bool>,
+    pub winner_ids: Vec<i64>,
+
+    pub method: String,
+}
+
+/// One name in a draw, with the flag templates need for the guest badge
+#[derive(Debug, Clone)]
+pub struct GiveawayEntrant {
+    pub game_player_id: Option<i64>,
+    pub username: String,
+    pub is_guest: bool,
+}
+
+impl GiveawayDraw {
+    pub fn pool_name(&self) -> &'static str {
+        if self.pool == GIVEAWAY_POOL_WINNERS {
+            return "Winners";
+        }
+
+        return "Everyone who scored";
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        return self.method == GIVEAWAY_METHOD_CHACHA8_SHUFFLE;
+    }
+
+    pub fn is_verified(&self) -> bool {
+        let draw_count = usize::try_from(self.draw_count).unwrap_or(0);
+
+        if self.entrant_ids.is_empty() {
+            return draw_chacha8_shuffle(self.seed, &self.entrants, draw_count) == self.winners;
+        }
+
+        if self.is_legacy() {
+            return draw_chacha8_shuffle(self.seed, &self.entrant_ids, draw_count)
+                == self.winner_ids;
+        }
+
+        return draw_winners(self.seed, &self.entrant_ids, draw_count) == self.winner_ids;
+    }
+
+    pub fn entrant_list(&self) -> Vec<GiveawayEntrant> {
+        return self
+            .entrants
+            .iter()
+            .enumerate()
+            .map(|(i, username)| GiveawayEntrant {
+                game_player_id: self.entrant_ids.get(i).copied(),
+                username: username.clone(),
+                is_guest: self.entrant_guests.get(i).copied().unwrap_or(false),
+            })
+            .collect();
+    }
+
+    pub fn winner_list(&self) -> Vec<GiveawayEntrant> {
+        let entrants = self.entrant_list();
+
+        if self.winner_ids.is_empty() {
+            return self
+                .winners
+                .iter()
+                .map(|username| GiveawayEntrant {
+                    game_player_id: None,
+                    username: username.clone(),
+                    is_guest: false,
+                })
+                .collect();
+        }
+
+        return self
+            .winner_ids
+            .iter()
+            .filter_map(|id| {
+                entrants
+                    .iter()
+                    .find(|entrant| entrant.game_player_id == Some(*id))
+                    .cloned()
+            })
+            .collect();
+    }
+}
+
+/// Picks winners one at a time without putting them back. Pick `i`, counting from 0, hashes
+/// the seed as 8 big-endian bytes followed by `i` as 4 big-endian bytes with SHA-256, reads the
+/// first 8 bytes of the hash as a big-endian u64 and takes it modulo the number of entrants
+/// left. That position, counting from 0, wins and leaves the list. Nothing here depends on
+/// a library's RNG, so anyone can re-run a draw with any SHA-256 tool
+pub fn draw_winners<T: Clone>(seed: i64, entrants: &[T], draw_count: usize) -> Vec<T> {
+    let mut remaining = entrants.to_vec();
+    let mut winners = vec![];
+
+    for i in 0..draw_count.min(entrants.len()) as u32 {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.to_be_bytes());
+        hasher.update(i.to_be_bytes());
+        let hash = hasher.finalize();
+
+        let mut head = [0u8; 8];
+        head.copy_from_slice(&hash[..8]);
+        let idx = u64::from_be_bytes(head) % remaining.len() as u64;
+
+        winners.push(remaining.remove(idx as usize));
+    }
+
+    return winners;
+}
+
+// How draws were made before GIVEAWAY_METHOD_SHA256_PICKS
+fn draw_chacha8_shuffle<T: Clone>(seed: i64, entrants: &[T], draw_count: usize) -> Vec<T> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+
+    let mut winners = entrants.to_vec();
+    winners.shuffle(&mut rng);
+    winners.truncate(draw_count);
+
+    return winners;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Worked out with Python's hashlib, so a change to the method or a dependency shows up here
+    #[test]
+    fn draws_match_known_answers() {
+        let entrants: Vec<i64> = (1..=10).collect();
+        assert_eq!(draw_winners(42, &entrants, 3), vec![10, 4, 9]);
+
+        let entrants: Vec<i64> = (100..120).collect();
+        assert_eq!(
+            draw_winners(-7, &entrants, 5),
+            vec![105, 100, 118, 109, 117]
+        );
+    }
+
+    #[test]
+    fn draws_everyone_when_asked_for_more_than_entered() {
+        assert_eq!(draw_winners(42, &[1i64, 2, 3], 10), vec![2, 3, 1]);
+    }
+}
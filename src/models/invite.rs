@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+pub const JOIN_REQUEST_PENDING: &str = "PENDING";
+pub const JOIN_REQUEST_APPROVED: &str = "APPROVED";
+pub const JOIN_REQUEST_DENIED: &str = "DENIED";
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameInvite {
+    pub game_invite_id: i32,
+    pub game_code: String,
+    pub twitch_login: String,
+    pub created_at: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameJoinRequest {
+    pub game_join_request_id: i32,
+    pub game_code: String,
+    pub user_id: String,
+    pub status: String,
+    pub decided_by: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct GameJoinRequestWithUser {
+    pub game_join_request_id: i32,
+    pub game_code: String,
+    pub user_id: String,
+    pub status: String,
+    pub decided_by: Option<String>,
+    pub created_at: i64,
+
+    pub username: String,
+}
@@ -33,3 +33,27 @@ pub use game_item_outcome::*;
 
 mod chat_message;
 pub use chat_message::*;
+
+mod giveaway_draw;
+pub use giveaway_draw::*;
+
+mod game_round;
+pub use game_round::*;
+
+mod cohost;
+pub use cohost::*;
+
+mod ban;
+pub use ban::*;
+
+mod point_adjustment;
+pub use point_adjustment::*;
+
+mod game_event;
+pub use game_event::*;
+
+mod invite;
+pub use invite::*;
+
+mod user_identity;
+pub use user_identity::*;
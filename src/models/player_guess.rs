@@ -7,5 +7,20 @@ pub struct PlayerGuess {
     pub game_code: String,
     pub player_id: i64,
     pub item_id: i64,
+    pub round_id: Option<i64>,
     pub outcome_id: Option<i64>,
+    pub received_at_ms: Option<i64>,
+    pub response_ms: Option<i64>,
+    pub bonus_points: i32,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct ResolvedGuess {
+    pub outcome_id: i64,
+    pub player_id: i64,
+    pub username: String,
+    pub user_id: String,
+    pub item_name: String,
+    pub bonus_points: i32,
+    pub is_correct: bool,
 }
@@ -0,0 +1,38 @@
+use super::is_guest_id;
+
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct PointAdjustment {
+    pub point_adjustment_id: i32,
+    pub game_code: String,
+    pub player_id: i64,
+    pub points: i32,
+    pub reason: String,
+    pub adjusted_by: Option<String>,
+    pub after_outcome_id: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct PointAdjustmentWithUsers {
+    pub point_adjustment_id: i32,
+    pub game_code: String,
+    pub player_id: i64,
+    pub points: i32,
+    pub reason: String,
+    pub adjusted_by: Option<String>,
+    pub after_outcome_id: Option<i64>,
+    pub created_at: i64,
+
+    pub user_id: String,
+    pub username: String,
+    pub adjusted_by_username: Option<String>,
+}
+
+impl PointAdjustmentWithUsers {
+    pub fn is_guest(&self) -> bool {
+        return is_guest_id(&self.user_id);
+    }
+}
@@ -13,6 +13,7 @@ pub struct SessionAuth {
     pub created_at: i64,
     pub expiry: i64,
     pub can_chat: bool,
+    pub can_check_eligibility: bool,
 }
 
 #[derive(sqlx::FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -25,9 +26,11 @@ pub struct SessionAuthWithUser {
     pub created_at: i64,
     pub expiry: i64,
     pub can_chat: bool,
+    pub can_check_eligibility: bool,
 
     pub username: String,
     pub twitch_login: String,
+    pub provider: String,
 }
 
 impl SessionAuthWithUser {
@@ -37,6 +40,7 @@ impl SessionAuthWithUser {
                 user_id: self.user_id.clone(),
                 username: self.username,
                 twitch_login: self.twitch_login,
+                provider: self.provider,
             },
             SessionAuth {
                 id: self.id,
@@ -47,6 +51,7 @@ impl SessionAuthWithUser {
                 created_at: self.created_at,
                 expiry: self.expiry,
                 can_chat: self.can_chat,
+                can_check_eligibility: self.can_check_eligibility,
             },
         );
     }
@@ -6,4 +6,22 @@ pub struct User {
     pub user_id: String,
     pub username: String,
     pub twitch_login: String,
+    pub provider: String,
+}
+
+pub const PROVIDER_TWITCH: &str = "twitch";
+pub const PROVIDER_OIDC: &str = "oidc";
+pub const PROVIDER_GUEST: &str = "guest";
+
+// Twitch user ids are numeric, so the prefix can't collide with a real account
+pub const GUEST_USER_PREFIX: &str = "guest:";
+
+pub fn is_guest_id(user_id: &str) -> bool {
+    return user_id.starts_with(GUEST_USER_PREFIX);
+}
+
+impl User {
+    pub fn is_guest(&self) -> bool {
+        return is_guest_id(&self.user_id);
+    }
 }
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use sqlx;
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct UserIdentity {
+    pub user_identity_id: i32,
+    pub user_id: String,
+    pub provider: String,
+    pub subject: String,
+    pub username: String,
+    pub created_at: i64,
+
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expiry: i64,
+    pub can_chat: bool,
+    pub can_check_eligibility: bool,
+}
@@ -116,27 +116,72 @@ pub struct PlayerAction {
 pub enum PlayerActionType {
     EnableClearGuesses,
     Join { new_players_count: i64 },
+    Leave { new_players_count: i64 },
     Guess { item_id: u64, new_guess_count: i32 },
     UndoGuess { item_id: u64, new_guess_count: i32 },
+    RequestJoin { pending_count: i64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct HostAction {
     pub game_code: String,
     pub typ: HostActionType,
+    // user_id of whoever ran the action, host or co-host, None for the server's own jobs
+    pub actor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum HostActionType {
+    Start,
     Lock,
-    Unlock,
+    Unlock { lock_at_ms: Option<i64> },
     ClearGuesses,
-    Choose { item_id: u64 },
+    Choose { item_ids: Vec<u64> },
     Enable { item_id: u64 },
     Disable { item_id: u64 },
+    SuddenDeath,
+    Kick { user_id: String },
+    AdjustPoints { user_id: String },
+    Pause,
+    Resume,
     Finish,
 }
 
+impl PlayerActionType {
+    /// Variant name, matches the key serde writes for the action
+    pub fn name(&self) -> &'static str {
+        return match self {
+            PlayerActionType::EnableClearGuesses => "EnableClearGuesses",
+            PlayerActionType::Join { .. } => "Join",
+            PlayerActionType::Leave { .. } => "Leave",
+            PlayerActionType::Guess { .. } => "Guess",
+            PlayerActionType::UndoGuess { .. } => "UndoGuess",
+            PlayerActionType::RequestJoin { .. } => "RequestJoin",
+        };
+    }
+}
+
+impl HostActionType {
+    /// Variant name, matches the key serde writes for the action
+    pub fn name(&self) -> &'static str {
+        return match self {
+            HostActionType::Start => "Start",
+            HostActionType::Lock => "Lock",
+            HostActionType::Unlock { .. } => "Unlock",
+            HostActionType::ClearGuesses => "ClearGuesses",
+            HostActionType::Choose { .. } => "Choose",
+            HostActionType::Enable { .. } => "Enable",
+            HostActionType::Disable { .. } => "Disable",
+            HostActionType::SuddenDeath => "SuddenDeath",
+            HostActionType::Kick { .. } => "Kick",
+            HostActionType::AdjustPoints { .. } => "AdjustPoints",
+            HostActionType::Pause => "Pause",
+            HostActionType::Resume => "Resume",
+            HostActionType::Finish => "Finish",
+        };
+    }
+}
+
 impl TryInto<PubsubMessage> for PlayerAction {
     type Error = crate::result::AppError;
 
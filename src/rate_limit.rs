@@ -0,0 +1,55 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// Guests cost nothing to make, so each client only makes a few a minute, and a game
+// only takes so many new ones a minute however many clients they come from
+pub const GUEST_CLIENT_JOIN_LIMIT: u32 = 5;
+pub const GUEST_JOIN_LIMIT: u32 = 120;
+pub const GUEST_JOIN_WINDOW: Duration = Duration::from_secs(60);
+
+pub const GUEST_GUESS_LIMIT: u32 = 20;
+pub const GUEST_GUESS_WINDOW: Duration = Duration::from_secs(60);
+
+const RATE_LIMIT_MAX_KEYS: usize = 10_000;
+
+/// Fixed window counter per key. Kept in memory, so each server instance counts on its own
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    hits: Mutex<HashMap<String, (u32, Instant)>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        return Self {
+            limit,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        };
+    }
+
+    /// Counts a hit against the key, false once the key has used up its window
+    pub fn check(&self, key: &str) -> bool {
+        let mut hits = self.hits.lock().unwrap();
+        if hits.len() >= RATE_LIMIT_MAX_KEYS {
+            hits.retain(|_, (_, started_at)| started_at.elapsed() < self.window);
+        }
+
+        let entry = hits.entry(key.to_string()).or_insert((0, Instant::now()));
+
+        if entry.1.elapsed() >= self.window {
+            *entry = (0, Instant::now());
+        }
+
+        if entry.0 >= self.limit {
+            return false;
+        }
+
+        entry.0 += 1;
+
+        return true;
+    }
+}